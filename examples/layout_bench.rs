@@ -0,0 +1,132 @@
+use indexed_priority_queue::compare::{MaxOrder, MinOrder};
+use indexed_priority_queue::hash_map::IndexedHashMap;
+use indexed_priority_queue::layout::{Indirect, Inline, Layout};
+use indexed_priority_queue::tie_break::NoTieBreak;
+use indexed_priority_queue::IndexedPriorityQueue;
+use ordered_float::OrderedFloat;
+use std::time::{Duration, Instant};
+
+// Compares the `Indirect` and `Inline` heap layouts on Dijkstra and VSIDS workloads backed by hash maps.
+// With `Inline`, comparisons read the priorities stored next to the indices on the heap instead of
+// probing the hash map, which avoids a cache miss per comparison on large queues.
+// Run with `cargo run --release --example layout_bench`.
+
+type Queue<Priority, C, L> = IndexedPriorityQueue<
+    usize,
+    IndexedHashMap<usize, Priority>,
+    IndexedHashMap<usize, usize>,
+    2,
+    C,
+    NoTieBreak,
+    L,
+>;
+
+/// Deterministic pseudo-random numbers for the workloads.
+fn lcg(state: &mut u64) -> u64 {
+    *state = state
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *state >> 33
+}
+
+/// Generates a random graph as lists of (neighbour, length) pairs.
+fn random_graph(nodes: usize, degree: usize) -> Vec<Vec<(usize, usize)>> {
+    let mut state = 1;
+    (0..nodes)
+        .map(|_| {
+            (0..degree)
+                .map(|_| {
+                    let neighbour = lcg(&mut state) as usize % nodes;
+                    let length = 1 + lcg(&mut state) as usize % 100;
+                    (neighbour, length)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Computes the sum of the shortest distances from node 0 to all reachable nodes.
+fn dijkstra<L: Layout<usize>>(graph: &[Vec<(usize, usize)>]) -> (usize, Duration) {
+    let start = Instant::now();
+    let mut queue =
+        Queue::<usize, MinOrder, L>::new(IndexedHashMap::default(), IndexedHashMap::default());
+    let mut settled = vec![false; graph.len()];
+    let mut total = 0;
+
+    queue.push(0, 0);
+    while let Some(node) = queue.pop() {
+        let distance = *queue.get_priority(node).unwrap();
+        settled[node] = true;
+        total += distance;
+
+        for &(neighbour, length) in &graph[node] {
+            let alternative = distance + length;
+            if !settled[neighbour]
+                && queue
+                    .get_priority(neighbour)
+                    .is_none_or(|&best| alternative < best)
+            {
+                queue.push(neighbour, alternative);
+            }
+        }
+    }
+
+    (total, start.elapsed())
+}
+
+/// Simulates the branching heuristic of a SAT-solver: variables are assigned in order of
+/// decreasing activity, and conflicts bump the activities of random variables.
+fn vsids<L: Layout<OrderedFloat<f64>>>(variables: usize, conflicts: usize) -> (usize, Duration) {
+    let start = Instant::now();
+    let mut queue = Queue::<OrderedFloat<f64>, MaxOrder, L>::new(
+        IndexedHashMap::default(),
+        IndexedHashMap::default(),
+    );
+    let mut state = 2;
+    let mut increment = 1.0;
+    let mut checksum: usize = 0;
+
+    for variable in 0..variables {
+        queue.push(variable, OrderedFloat(0.0));
+    }
+
+    for _ in 0..conflicts {
+        // Branch on a few variables.
+        let mut assigned = Vec::new();
+        for _ in 0..16 {
+            if let Some(variable) = queue.pop() {
+                checksum = checksum.wrapping_mul(31).wrapping_add(variable);
+                assigned.push(variable);
+            }
+        }
+
+        // Bump the activities of the variables involved in the conflict.
+        for _ in 0..8 {
+            let variable = lcg(&mut state) as usize % variables;
+            queue.update_down(variable).0 += increment;
+        }
+        increment *= 1.05;
+        if increment > 1e100 {
+            queue.update_priorities_order_preserving(|activity| activity.0 *= 1e-100);
+            increment *= 1e-100;
+        }
+
+        // Backtrack.
+        queue.extend_indices(assigned);
+    }
+
+    (checksum, start.elapsed())
+}
+
+pub fn main() {
+    let graph = random_graph(200_000, 8);
+    let (indirect_total, indirect_time) = dijkstra::<Indirect>(&graph);
+    let (inline_total, inline_time) = dijkstra::<Inline>(&graph);
+    assert_eq!(indirect_total, inline_total);
+    println!("dijkstra: indirect {indirect_time:?}, inline {inline_time:?}");
+
+    let (indirect_checksum, indirect_time) = vsids::<Indirect>(100_000, 20_000);
+    let (inline_checksum, inline_time) = vsids::<Inline>(100_000, 20_000);
+    assert_eq!(indirect_checksum, inline_checksum);
+    println!("vsids: indirect {indirect_time:?}, inline {inline_time:?}");
+}
@@ -1,114 +1,466 @@
-use crate::indexed::Indexed;
+use crate::compare::Compare;
+use crate::indexed::{Indexed, Position};
+use crate::layout::Layout;
+use crate::slots::Slots;
+use crate::tie_break::TieBreak;
+use crate::IndexedPriorityQueue;
+use std::fmt::{self, Debug, Formatter};
+use std::marker::PhantomData;
 use std::mem;
 
+/// Maps the indices of an array map to dense slots.
+pub trait IndexMapper {
+    /// The indices that are mapped.
+    type Index: Copy;
+
+    /// Returns the slot of the index, or `None` if the index has no slot.
+    fn slot(&self, index: Self::Index) -> Option<usize>;
+}
+
+/// Maps the `usize` indices `OFFSET..` to the slots `0..`. This is the default mapper.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Offset<const OFFSET: usize = 0>;
+
+impl<const OFFSET: usize> IndexMapper for Offset<OFFSET> {
+    type Index = usize;
+
+    fn slot(&self, index: usize) -> Option<usize> {
+        index.checked_sub(OFFSET)
+    }
+}
+
+/// Maps indices that convert into dense `usize` values, such as `NodeId(u32)` newtypes
+/// implementing `From<NodeId> for usize`, to the slots given by that conversion.
+pub struct Dense<I>(PhantomData<fn(I)>);
+
+impl<I> Debug for Dense<I> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("Dense")
+    }
+}
+
+impl<I> Default for Dense<I> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<I> Clone for Dense<I> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<I> Copy for Dense<I> {}
+
+impl<I: Copy + Into<usize>> IndexMapper for Dense<I> {
+    type Index = I;
+
+    fn slot(&self, index: I) -> Option<usize> {
+        Some(index.into())
+    }
+}
+
+/// Maps the non-zero `i32` literals of SAT-solvers in the DIMACS encoding to slots,
+/// placing each literal `v` at `2 * (|v| - 1)` and its negation right after it.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Literals;
+
+impl IndexMapper for Literals {
+    type Index = i32;
+
+    fn slot(&self, index: i32) -> Option<usize> {
+        let variable = (index.unsigned_abs() as usize).checked_sub(1)?;
+        Some(2 * variable + usize::from(index < 0))
+    }
+}
+
+/// Maps `(x, y)` coordinates on a grid of the given width to slots, row by row.
+#[derive(Debug, Copy, Clone)]
+pub struct Grid {
+    pub width: usize,
+}
+
+impl IndexMapper for Grid {
+    type Index = (usize, usize);
+
+    fn slot(&self, (x, y): (usize, usize)) -> Option<usize> {
+        if x < self.width {
+            y.checked_mul(self.width)?.checked_add(x)
+        } else {
+            None
+        }
+    }
+}
+
+/// Priorities map backed by a fixed size array, whose indices are mapped to slots by `M`.
+///
+/// Clearing the map takes constant time, since every slot is stamped with a generation.
+/// Cleared and removed priorities remain stored until their slot is set again or the map is dropped.
 #[derive(Debug)]
-pub struct ArrayPriorityMap<Priority, const N: usize = 0>(Box<[Priority]>);
+pub struct ArrayPriorityMap<Priority, M = Offset>(Slots<Option<Priority>>, M);
 
+/// Positions map backed by a fixed size array, whose indices are mapped to slots by `M`.
+/// Positions are stored as `P`, with `P::NONE` marking slots without a position, see [`Position`].
+///
+/// The slots carry no generation stamps, so that a slot takes exactly the size of `P`.
+/// The queue clears its positions by unsetting only the slots of the indices on the heap.
 #[derive(Debug)]
-pub struct ArrayPositionMap<const N: usize = 0>(Box<[usize]>);
+pub struct ArrayPositionMap<M = Offset, P = usize>(Box<[P]>, M);
 
-impl<Priority: Clone, const OFFSET: usize> Indexed for ArrayPriorityMap<Priority, OFFSET> {
-    type Index = usize;
+impl<Priority, M: IndexMapper> ArrayPriorityMap<Priority, M> {
+    /// Constructs a map from the priorities of the slots, using the given mapper.
+    pub fn with_mapper(priorities: Box<[Priority]>, mapper: M) -> Self {
+        let priorities = priorities.into_vec().into_iter().map(Some).collect();
+        Self(Slots::new(priorities, |_| true), mapper)
+    }
+
+    /// Grows the map to `n` slots, where the new slots initially have no priority.
+    /// Does nothing if the map is already large enough.
+    ///
+    /// Time complexity: `O(n)`
+    pub fn grow(&mut self, n: usize) {
+        self.0.grow(n, || None);
+    }
+
+    /// Returns the slot of the index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index has no slot.
+    fn slot(&self, index: M::Index) -> usize {
+        self.1.slot(index).expect("index is out of range")
+    }
+}
+
+impl<M: IndexMapper, P: Position> ArrayPositionMap<M, P> {
+    /// Constructs a map with `n` slots without positions, using the given mapper.
+    pub fn with_mapper(n: usize, mapper: M) -> Self {
+        Self(vec![P::NONE; n].into_boxed_slice(), mapper)
+    }
+
+    /// Grows the map to `n` slots.
+    /// Does nothing if the map is already large enough.
+    ///
+    /// Time complexity: `O(n)`
+    pub fn grow(&mut self, n: usize) {
+        if n > self.0.len() {
+            let mut positions = mem::take(&mut self.0).into_vec();
+            positions.resize(n, P::NONE);
+            self.0 = positions.into_boxed_slice();
+        }
+    }
+
+    /// Returns the slot of the index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index has no slot.
+    fn slot(&self, index: M::Index) -> usize {
+        self.1.slot(index).expect("index is out of range")
+    }
+}
+
+impl<Priority, M: IndexMapper> Indexed for ArrayPriorityMap<Priority, M> {
+    type Index = M::Index;
     type Output = Priority;
 
     fn get(&self, index: Self::Index) -> Option<&Self::Output> {
-        let index = index - OFFSET;
+        let slot = self.1.slot(index)?;
 
-        Some(&self.0[index])
+        self.0.get(slot)?.as_ref()
     }
 
     fn get_mut(&mut self, index: Self::Index) -> Option<&mut Self::Output> {
-        let index = index - OFFSET;
+        let slot = self.1.slot(index)?;
 
-        Some(&mut self.0[index])
+        self.0.get_mut(slot)?.as_mut()
     }
 
-    fn insert(&mut self, index: Self::Index, mut value: Self::Output) -> Option<Self::Output> {
-        let index = index - OFFSET;
+    fn insert(&mut self, index: Self::Index, value: Self::Output) -> Option<Self::Output> {
+        let slot = self.slot(index);
 
-        let old_value = &mut self.0[index];
-        mem::swap(old_value, &mut value);
-        Some(value)
+        self.0.set(slot, Some(value)).flatten()
     }
 
     fn remove(&mut self, index: Self::Index) -> Option<Self::Output> {
-        let index = index - OFFSET;
+        let slot = self.slot(index);
 
-        Some(self.0[index].clone())
+        self.0.unset(slot)?.take()
     }
 
-    fn clear(&mut self) {}
+    fn clear(&mut self) {
+        self.0.clear();
+    }
 
-    fn iter(&mut self) -> impl Iterator<Item = &Self::Output> {
-        self.0.iter()
+    fn in_range(&self, index: Self::Index) -> bool {
+        self.1.slot(index).is_some_and(|slot| slot < self.0.len())
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &Self::Output> {
+        self.0.iter().flatten()
     }
 
     fn iter_mut(&mut self) -> impl Iterator<Item = &mut Self::Output> {
-        self.0.iter_mut()
+        self.0.iter_mut().flatten()
     }
 }
 
-impl<const OFFSET: usize> Indexed for ArrayPositionMap<OFFSET> {
-    type Index = usize;
-    type Output = usize;
+impl<M: IndexMapper, P: Position> Indexed for ArrayPositionMap<M, P> {
+    type Index = M::Index;
+    type Output = P;
 
     fn get(&self, index: Self::Index) -> Option<&Self::Output> {
-        let index = index - OFFSET;
+        let slot = self.1.slot(index)?;
 
-        match &self.0[index] {
-            &usize::MAX => None,
-            position => Some(position),
-        }
+        self.0.get(slot).filter(|position| **position != P::NONE)
     }
 
     fn get_mut(&mut self, index: Self::Index) -> Option<&mut Self::Output> {
-        let index = index - OFFSET;
+        let slot = self.1.slot(index)?;
 
-        match &mut self.0[index] {
-            &mut usize::MAX => None,
-            position => Some(position),
-        }
+        self.0
+            .get_mut(slot)
+            .filter(|position| **position != P::NONE)
     }
 
     fn insert(&mut self, index: Self::Index, value: Self::Output) -> Option<Self::Output> {
-        let index = index - OFFSET;
+        let slot = self.slot(index);
+
+        Some(mem::replace(&mut self.0[slot], value)).filter(|position| *position != P::NONE)
+    }
+
+    fn remove(&mut self, index: Self::Index) -> Option<Self::Output> {
+        let slot = self.slot(index);
+
+        Some(mem::replace(&mut self.0[slot], P::NONE)).filter(|position| *position != P::NONE)
+    }
+
+    /// Time complexity: `O(n)`
+    fn clear(&mut self) {
+        self.0.fill(P::NONE);
+    }
+
+    fn in_range(&self, index: Self::Index) -> bool {
+        self.1.slot(index).is_some_and(|slot| slot < self.0.len())
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &Self::Output> {
+        self.0.iter().filter(|position| **position != P::NONE)
+    }
+
+    fn iter_mut(&mut self) -> impl Iterator<Item = &mut Self::Output> {
+        self.0.iter_mut().filter(|position| **position != P::NONE)
+    }
+}
+
+impl<Priority, M: IndexMapper + Default> From<Box<[Priority]>> for ArrayPriorityMap<Priority, M> {
+    fn from(value: Box<[Priority]>) -> Self {
+        Self::with_mapper(value, M::default())
+    }
+}
+
+/// Positions equal to `P::NONE` are unset.
+impl<M: IndexMapper + Default, P: Position> From<Box<[P]>> for ArrayPositionMap<M, P> {
+    fn from(value: Box<[P]>) -> Self {
+        Self(value, M::default())
+    }
+}
+
+/// Priorities map backed by a fixed size array, in which every slot without a priority
+/// has the default priority. Indices are mapped to slots by `M`.
+///
+/// Clearing the map takes constant time, since every slot is stamped with a generation.
+#[derive(Debug)]
+pub struct DefaultArrayMap<Priority, M = Offset>(Slots<Priority>, Priority, M);
+
+impl<Priority: Clone, M: IndexMapper> DefaultArrayMap<Priority, M> {
+    /// Constructs a map with `n` slots, which all have the given default priority.
+    pub fn new(n: usize, default: Priority) -> Self
+    where
+        M: Default,
+    {
+        Self::with_mapper(n, default, M::default())
+    }
+
+    /// Constructs a map with `n` slots, which all have the given default priority, using the given mapper.
+    pub fn with_mapper(n: usize, default: Priority, mapper: M) -> Self {
+        Self(
+            Slots::new(vec![default.clone(); n], |_| false),
+            default,
+            mapper,
+        )
+    }
 
-        match mem::replace(&mut self.0[index], value) {
-            usize::MAX => None,
-            position => Some(position),
+    /// Grows the map to `n` slots, where the new slots have the default priority.
+    /// Does nothing if the map is already large enough.
+    ///
+    /// Time complexity: `O(n)`
+    pub fn grow(&mut self, n: usize) {
+        self.0.grow(n, || self.1.clone());
+    }
+
+    /// Returns the slot of the index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index has no slot.
+    fn slot(&self, index: M::Index) -> usize {
+        self.2.slot(index).expect("index is out of range")
+    }
+}
+
+impl<Priority: Clone, M: IndexMapper> Indexed for DefaultArrayMap<Priority, M> {
+    type Index = M::Index;
+    type Output = Priority;
+
+    fn get(&self, index: Self::Index) -> Option<&Self::Output> {
+        let slot = self.2.slot(index).filter(|slot| *slot < self.0.len())?;
+
+        Some(self.0.get(slot).unwrap_or(&self.1))
+    }
+
+    fn get_mut(&mut self, index: Self::Index) -> Option<&mut Self::Output> {
+        let slot = self.2.slot(index).filter(|slot| *slot < self.0.len())?;
+
+        if self.0.get(slot).is_none() {
+            self.0.set(slot, self.1.clone());
         }
+        self.0.get_mut(slot)
+    }
+
+    fn insert(&mut self, index: Self::Index, value: Self::Output) -> Option<Self::Output> {
+        let slot = self.slot(index);
+
+        self.0.set(slot, value)
     }
 
+    /// Slots without a priority of their own yield the default priority.
     fn remove(&mut self, index: Self::Index) -> Option<Self::Output> {
-        let index = index - OFFSET;
+        let slot = self.slot(index);
+        let default = self.1.clone();
 
-        match mem::replace(&mut self.0[index], usize::MAX) {
-            usize::MAX => None,
-            position => Some(position),
+        match self.0.unset(slot) {
+            Some(priority) => Some(mem::replace(priority, default)),
+            None => Some(default),
         }
     }
 
     fn clear(&mut self) {
-        self.0.fill(usize::MAX);
+        self.0.clear();
+    }
+
+    fn in_range(&self, index: Self::Index) -> bool {
+        self.2.slot(index).is_some_and(|slot| slot < self.0.len())
     }
 
-    fn iter(&mut self) -> impl Iterator<Item = &Self::Output> {
-        self.0.iter().filter(|v| **v != usize::MAX)
+    fn iter(&self) -> impl Iterator<Item = &Self::Output> {
+        self.0.iter()
     }
 
     fn iter_mut(&mut self) -> impl Iterator<Item = &mut Self::Output> {
-        self.0.iter_mut().filter(|v| **v != usize::MAX)
+        self.0.iter_mut()
     }
 }
 
-impl<Priority, const OFFSET: usize> From<Box<[Priority]>> for ArrayPriorityMap<Priority, OFFSET> {
-    fn from(value: Box<[Priority]>) -> Self {
-        Self(value)
+impl<Priority, M, P, const D: usize, C, T, L>
+    IndexedPriorityQueue<M::Index, DefaultArrayMap<Priority, M>, ArrayPositionMap<M, P>, D, C, T, L>
+where
+    Priority: Clone,
+    M: IndexMapper,
+    P: Position,
+    C: Compare<Priority>,
+    T: TieBreak,
+    L: Layout<Priority>,
+{
+    /// Constructs a new, empty queue with `n` slots, whose indices all have the given default priority.
+    /// For the default mapper, these are the indices `OFFSET..OFFSET + n`.
+    pub fn with_index_space(n: usize, default: Priority) -> Self
+    where
+        M: Clone + Default,
+        C: Default,
+        T: Default,
+    {
+        Self::with_index_space_and_mapper(n, default, M::default())
+    }
+
+    /// Constructs a new, empty queue with `n` slots, whose indices are mapped to slots by `mapper`
+    /// and all have the given default priority.
+    pub fn with_index_space_and_mapper(n: usize, default: Priority, mapper: M) -> Self
+    where
+        M: Clone,
+        C: Default,
+        T: Default,
+    {
+        Self::with_capacity(
+            DefaultArrayMap::with_mapper(n, default, mapper.clone()),
+            ArrayPositionMap::with_mapper(n, mapper),
+            n,
+        )
+    }
+
+    /// Grows the queue to `n` slots, whose new indices have the default priority.
+    /// Does nothing if the queue already has enough slots.
+    ///
+    /// Time complexity: `O(n)`
+    pub fn grow_index_space(&mut self, n: usize) {
+        self.priorities.grow(n);
+        self.positions.grow(n);
     }
 }
 
-impl<const OFFSET: usize> From<Box<[usize]>> for ArrayPositionMap<OFFSET> {
-    fn from(value: Box<[usize]>) -> Self {
-        Self(value)
+impl<Priority, M, P, const D: usize, C, T, L>
+    IndexedPriorityQueue<
+        M::Index,
+        ArrayPriorityMap<Priority, M>,
+        ArrayPositionMap<M, P>,
+        D,
+        C,
+        T,
+        L,
+    >
+where
+    M: IndexMapper,
+    P: Position,
+    C: Compare<Priority>,
+    T: TieBreak,
+    L: Layout<Priority>,
+{
+    /// Constructs a new, empty queue with `n` slots, whose indices all start out with the priority `default`.
+    /// For the default mapper, these are the indices `OFFSET..OFFSET + n`.
+    pub fn with_index_space(n: usize, default: Priority) -> Self
+    where
+        Priority: Clone,
+        M: Clone + Default,
+        C: Default,
+        T: Default,
+    {
+        Self::with_index_space_and_mapper(n, default, M::default())
+    }
+
+    /// Constructs a new, empty queue with `n` slots, whose indices are mapped to slots by `mapper`
+    /// and all start out with the priority `default`.
+    pub fn with_index_space_and_mapper(n: usize, default: Priority, mapper: M) -> Self
+    where
+        Priority: Clone,
+        M: Clone,
+        C: Default,
+        T: Default,
+    {
+        Self::with_capacity(
+            ArrayPriorityMap::with_mapper(vec![default; n].into_boxed_slice(), mapper.clone()),
+            ArrayPositionMap::with_mapper(n, mapper),
+            n,
+        )
+    }
+
+    /// Grows the queue to `n` slots.
+    /// The new indices have no priority, and can be pushed afterwards.
+    /// Does nothing if the queue already has enough slots.
+    ///
+    /// Time complexity: `O(n)`
+    pub fn grow_index_space(&mut self, n: usize) {
+        self.priorities.grow(n);
+        self.positions.grow(n);
     }
 }
@@ -0,0 +1,97 @@
+use crate::compare::Compare;
+use crate::indexed::Indexed;
+use crate::layout::Layout;
+use crate::tie_break::TieBreak;
+use crate::IndexedPriorityQueue;
+use std::collections::BTreeMap;
+
+/// Map backed by a `BTreeMap`, which iterates in the order of its keys.
+#[derive(Debug)]
+pub struct IndexedBTreeMap<K, V>(BTreeMap<K, V>);
+
+impl<K, V> Default for IndexedBTreeMap<K, V> {
+    fn default() -> Self {
+        Self(BTreeMap::new())
+    }
+}
+
+impl<K: Ord, V> IndexedBTreeMap<K, V> {
+    /// Returns an iterator over the index-value pairs, in the order of the indices.
+    pub fn pairs(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.0.iter()
+    }
+}
+
+impl<K: Ord, V> Indexed for IndexedBTreeMap<K, V> {
+    type Index = K;
+    type Output = V;
+
+    fn get(&self, index: Self::Index) -> Option<&Self::Output> {
+        self.0.get(&index)
+    }
+
+    fn get_mut(&mut self, index: Self::Index) -> Option<&mut Self::Output> {
+        self.0.get_mut(&index)
+    }
+
+    fn insert(&mut self, index: Self::Index, value: Self::Output) -> Option<Self::Output> {
+        self.0.insert(index, value)
+    }
+
+    fn remove(&mut self, index: Self::Index) -> Option<Self::Output> {
+        self.0.remove(&index)
+    }
+
+    fn clear(&mut self) {
+        self.0.clear()
+    }
+
+    fn get_or_insert_with(
+        &mut self,
+        index: Self::Index,
+        default: impl FnOnce() -> Self::Output,
+    ) -> &mut Self::Output {
+        self.0.entry(index).or_insert_with(default)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &Self::Output> {
+        self.0.values()
+    }
+
+    fn iter_mut(&mut self) -> impl Iterator<Item = &mut Self::Output> {
+        self.0.values_mut()
+    }
+}
+
+impl<K, V> From<BTreeMap<K, V>> for IndexedBTreeMap<K, V> {
+    fn from(value: BTreeMap<K, V>) -> Self {
+        Self(value)
+    }
+}
+
+impl<Index, Priority, const D: usize, C, T, L>
+    IndexedPriorityQueue<
+        Index,
+        IndexedBTreeMap<Index, Priority>,
+        IndexedBTreeMap<Index, usize>,
+        D,
+        C,
+        T,
+        L,
+    >
+where
+    Index: Copy + Ord,
+    C: Compare<Priority>,
+    T: TieBreak,
+    L: Layout<Priority>,
+{
+    /// Returns an iterator over the indices in the queue and their priorities, in the order of the indices.
+    ///
+    /// Time complexity: `O(n log n)` for the whole iteration
+    pub fn iter_ordered(&self) -> impl Iterator<Item = (Index, &Priority)> {
+        self.positions
+            .0
+            .iter()
+            .map(|(index, position)| (*index, self.priority_at(*position)))
+    }
+}
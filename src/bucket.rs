@@ -0,0 +1,359 @@
+use crate::indexed::Indexed;
+use std::ops::{Deref, DerefMut};
+
+/// Indexed Priority Queue backed by a cyclic bucket queue, as used by Dial's algorithm.
+///
+/// The queue is specialised for small integer priorities: all priorities in the queue must lie
+/// within a window of `range` consecutive values starting at the smallest one, such as
+/// `last..=last + max_weight` when running Dijkstra with edge weights of at most `max_weight`.
+/// The priority `p` is stored in bucket `p % range`, so buckets are reused as the window moves.
+/// Priorities outside the window are rejected in debug builds and when the `checked` feature is enabled.
+///
+/// The positions map stores the offset of each index within its bucket.
+#[derive(Debug)]
+pub struct BucketIPQ<Index, Priorities, Positions>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index, Output = usize>,
+    Positions: Indexed<Index = Index, Output = usize>,
+{
+    /// The priorities associated with indexes on the heap.
+    priorities: Priorities,
+    /// The offsets of the indexes within their buckets.
+    positions: Positions,
+    /// The buckets storing the indexes, one per priority in the window.
+    buckets: Vec<Vec<Index>>,
+    /// A lower bound for the priorities in the queue, the start of the window.
+    cursor: usize,
+    /// The number of indices in the queue.
+    len: usize,
+}
+
+impl<Index, Priorities, Positions> BucketIPQ<Index, Priorities, Positions>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index, Output = usize>,
+    Positions: Indexed<Index = Index, Output = usize>,
+{
+    /// Constructs a new, empty `BucketIPQ` for priorities within a window of `range` consecutive values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is zero.
+    pub fn new(
+        priorities: impl Into<Priorities>,
+        positions: impl Into<Positions>,
+        range: usize,
+    ) -> Self {
+        assert!(range > 0, "the range of a `BucketIPQ` must not be zero");
+        Self {
+            priorities: priorities.into(),
+            positions: positions.into(),
+            buckets: (0..range).map(|_| Vec::new()).collect(),
+            cursor: 0,
+            len: 0,
+        }
+    }
+
+    /// Constructs a new, empty `BucketIPQ` with default maps, see [`BucketIPQ::new`].
+    pub fn with_range(range: usize) -> Self
+    where
+        Priorities: Default,
+        Positions: Default,
+    {
+        Self::new(Priorities::default(), Positions::default(), range)
+    }
+
+    /// Returns the number of consecutive priorities the queue can hold at once.
+    pub fn range(&self) -> usize {
+        self.buckets.len()
+    }
+
+    /// Returns the number of indices in the queue.
+    ///
+    /// Time complexity: `O(1)`
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the queue contains no indices.
+    ///
+    /// Time complexity: `O(1)`
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if the queue contains the specified index.
+    ///
+    /// Time complexity: `O(1)`
+    pub fn contains(&self, index: Index) -> bool {
+        self.positions.contains(index)
+    }
+
+    /// Returns the priority associated with the specified index, or `None`, if the index has no priority.
+    ///
+    /// Time complexity: `O(1)`
+    pub fn get_priority(&self, index: Index) -> Option<&usize> {
+        self.priorities.get(index)
+    }
+
+    /// Returns the index associated with the smallest priority in the queue, or `None` if it is empty.
+    ///
+    /// Time complexity: `O(range)`
+    pub fn min(&self) -> Option<&Index> {
+        if self.is_empty() {
+            return None;
+        }
+
+        (self.cursor..)
+            .take(self.range())
+            .find_map(|priority| self.buckets[priority % self.range()].last())
+    }
+
+    /// Returns the smallest priority in the queue, or `None`, if it is empty.
+    ///
+    /// Time complexity: `O(range)`
+    pub fn min_priority(&self) -> Option<&usize> {
+        self.min().map(|index| self.priorities.index(*index))
+    }
+
+    /// Inserts an index-priority pair into the queue.
+    /// Returns the previous priority associated with the index, if it existed.
+    ///
+    /// Time complexity: `O(1)`
+    pub fn push(&mut self, index: Index, value: usize) -> Option<usize> {
+        if self.positions.contains(index) {
+            let old_priority = *self.priorities.index(index);
+            self.remove_from_bucket(index, old_priority);
+            self.priorities.insert(index, value);
+            self.insert_into_bucket(index, value);
+            Some(old_priority)
+        } else {
+            let old_priority = self.priorities.insert(index, value);
+            self.restore_index(index);
+            old_priority
+        }
+    }
+
+    /// Reinserts a previously removed index into the queue with its last associated value.
+    ///
+    /// Time complexity: `O(1)`
+    pub fn restore_index(&mut self, index: Index) {
+        if !self.positions.contains(index) {
+            let priority = *self.priorities.index(index);
+            if self.is_empty() {
+                self.cursor = priority;
+            }
+            self.insert_into_bucket(index, priority);
+            self.len += 1;
+        }
+    }
+
+    /// Removes and returns the index associated with the smallest priority in the queue, or `None` if it is empty.
+    ///
+    /// Time complexity: `O(range)`, `O(1)` amortized for monotone workloads
+    pub fn pop(&mut self) -> Option<Index> {
+        if self.is_empty() {
+            return None;
+        }
+
+        while self.buckets[self.cursor % self.range()].is_empty() {
+            self.cursor += 1;
+        }
+
+        let bucket = self.cursor % self.range();
+        let index = self.buckets[bucket].pop()?;
+        self.positions.remove(index);
+        self.len -= 1;
+        Some(index)
+    }
+
+    /// Removes the specified index and its associated priority from the queue.
+    /// Returns the removed priority.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index has no associated priority.
+    ///
+    /// Time complexity: `O(1)`
+    pub fn remove(&mut self, index: Index) -> usize {
+        self.remove_index(index);
+        self.priorities.remove(index).unwrap()
+    }
+
+    /// Removes the specified index from the queue, retaining its associated priority.
+    ///
+    /// Time complexity: `O(1)`
+    pub fn remove_index(&mut self, index: Index) {
+        if self.positions.contains(index) {
+            self.remove_from_bucket(index, *self.priorities.index(index));
+            self.len -= 1;
+        }
+    }
+
+    /// Clears all indices and their priorities from the queue.
+    pub fn clear(&mut self) {
+        self.clear_indices();
+        self.priorities.clear();
+    }
+
+    /// Clears all indices from the queue.
+    ///
+    /// Time complexity: `O(n + range)`
+    pub fn clear_indices(&mut self) {
+        for &index in self.buckets.iter().flatten() {
+            self.positions.remove(index);
+        }
+        self.buckets.iter_mut().for_each(Vec::clear);
+        self.len = 0;
+    }
+
+    /// Decreases the priority associated with the specified index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index has no associated priority.
+    ///
+    /// Time complexity: `O(1)`
+    pub fn update_down(&mut self, index: Index) -> BucketMutRef<'_, Index, Priorities, Positions> {
+        self.update(index, true)
+    }
+
+    /// Modifies the priority associated with the specified index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index has no associated priority.
+    ///
+    /// Time complexity: `O(1)`
+    pub fn update_dyn(&mut self, index: Index) -> BucketMutRef<'_, Index, Priorities, Positions> {
+        self.update(index, false)
+    }
+
+    /// Returns a guard to the priority associated with the specified index.
+    fn update(
+        &mut self,
+        index: Index,
+        _decrease: bool,
+    ) -> BucketMutRef<'_, Index, Priorities, Positions> {
+        BucketMutRef {
+            old_value: *self.priorities.index(index),
+            queue: self,
+            index,
+            #[cfg(any(debug_assertions, feature = "checked"))]
+            decrease: _decrease,
+        }
+    }
+
+    /// Appends the index to the bucket of the given priority, moving the start of the window
+    /// if the priority lies outside of it.
+    ///
+    /// Time complexity: `O(1)`, except for moving the window, which takes `O(range)`
+    fn insert_into_bucket(&mut self, index: Index, priority: usize) {
+        if priority - self.cursor.min(priority) >= self.range() {
+            self.advance(priority);
+        }
+        if priority < self.cursor {
+            // The window moves down to the new priority, so the largest priorities in the queue
+            // must not fall out of its end.
+            #[cfg(any(debug_assertions, feature = "checked"))]
+            assert!(
+                (priority + self.range()..self.cursor + self.range())
+                    .all(|priority| self.buckets[priority % self.range()].is_empty()),
+                "priorities must lie within `range` of the smallest priority in the queue"
+            );
+            self.cursor = priority;
+        }
+        #[cfg(any(debug_assertions, feature = "checked"))]
+        assert!(
+            priority - self.cursor < self.range(),
+            "priorities must lie within `range` of the smallest priority in the queue"
+        );
+
+        let bucket = priority % self.range();
+        self.positions.insert(index, self.buckets[bucket].len());
+        self.buckets[bucket].push(index);
+    }
+
+    /// Moves the start of the window to the smallest priority in the buckets,
+    /// or to the given priority if the buckets are empty.
+    fn advance(&mut self, priority: usize) {
+        match (0..self.range())
+            .find(|offset| !self.buckets[(self.cursor + offset) % self.range()].is_empty())
+        {
+            Some(offset) => self.cursor += offset,
+            None => self.cursor = priority,
+        }
+    }
+
+    /// Removes the index from the bucket of the given priority.
+    fn remove_from_bucket(&mut self, index: Index, priority: usize) {
+        let offset = self.positions.remove(index).unwrap();
+        let bucket = priority % self.range();
+        let bucket = &mut self.buckets[bucket];
+        bucket.swap_remove(offset);
+        if let Some(moved) = bucket.get(offset) {
+            *self.positions.index_mut(*moved) = offset;
+        }
+    }
+}
+
+/// Guard to the priority associated with an index of a `BucketIPQ`, which moves the index to its
+/// new bucket when dropped.
+pub struct BucketMutRef<'a, Index, Priorities, Positions>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index, Output = usize>,
+    Positions: Indexed<Index = Index, Output = usize>,
+{
+    queue: &'a mut BucketIPQ<Index, Priorities, Positions>,
+    index: Index,
+    old_value: usize,
+    /// Whether the priority may only decrease, which is checked when the guard is dropped.
+    #[cfg(any(debug_assertions, feature = "checked"))]
+    decrease: bool,
+}
+
+impl<'a, Index, Priorities, Positions> Deref for BucketMutRef<'a, Index, Priorities, Positions>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index, Output = usize>,
+    Positions: Indexed<Index = Index, Output = usize>,
+{
+    type Target = usize;
+
+    fn deref(&self) -> &Self::Target {
+        self.queue.priorities.index(self.index)
+    }
+}
+
+impl<'a, Index, Priorities, Positions> DerefMut for BucketMutRef<'a, Index, Priorities, Positions>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index, Output = usize>,
+    Positions: Indexed<Index = Index, Output = usize>,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.queue.priorities.index_mut(self.index)
+    }
+}
+
+impl<'a, Index, Priorities, Positions> Drop for BucketMutRef<'a, Index, Priorities, Positions>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index, Output = usize>,
+    Positions: Indexed<Index = Index, Output = usize>,
+{
+    fn drop(&mut self) {
+        if self.queue.positions.contains(self.index) {
+            let new_value = *self.queue.priorities.index(self.index);
+            #[cfg(any(debug_assertions, feature = "checked"))]
+            assert!(
+                !self.decrease || new_value <= self.old_value,
+                "`update_down` must not increase the priority"
+            );
+            self.queue.remove_from_bucket(self.index, self.old_value);
+            self.queue.insert_into_bucket(self.index, new_value);
+        }
+    }
+}
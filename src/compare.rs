@@ -0,0 +1,34 @@
+use std::cmp::Ordering;
+
+/// Defines the order in which priorities are popped from a queue.
+/// Priorities that compare as `Ordering::Less` are popped first.
+pub trait Compare<T: ?Sized> {
+    fn compare(&self, a: &T, b: &T) -> Ordering;
+}
+
+/// Pops the smallest priority first, according to its `Ord` implementation.
+#[derive(Default, Debug, Copy, Clone)]
+pub struct MinOrder;
+
+/// Pops the largest priority first, according to its `Ord` implementation.
+#[derive(Default, Debug, Copy, Clone)]
+pub struct MaxOrder;
+
+impl<T: Ord + ?Sized> Compare<T> for MinOrder {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        a.cmp(b)
+    }
+}
+
+impl<T: Ord + ?Sized> Compare<T> for MaxOrder {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        b.cmp(a)
+    }
+}
+
+/// Closures can be used as (stateful) comparators.
+impl<T: ?Sized, F: Fn(&T, &T) -> Ordering> Compare<T> for F {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        self(a, b)
+    }
+}
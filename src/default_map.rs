@@ -1,11 +1,34 @@
+use crate::compare::Compare;
+use crate::hash_map::IndexedHashMap;
 use crate::indexed::Indexed;
+use crate::layout::Layout;
+use crate::tie_break::TieBreak;
+use crate::IndexedPriorityQueue;
 use std::collections::HashMap;
-use std::hash::Hash;
+use std::hash::{BuildHasher, Hash, RandomState};
 
-#[derive(Default, Debug)]
-pub struct DefaultMap<K, V>(HashMap<K, V>, V);
+/// Priorities map in which every index without a priority has the default priority.
+/// The default is `V::default()`, unless constructed with [`DefaultMap::with_default`].
+///
+/// The default is stored along with the function that makes a new default priority from it,
+/// so that only maps constructed with [`DefaultMap::with_default`] require `V: Clone`.
+#[derive(Debug)]
+pub struct DefaultMap<K, V, S = RandomState>(HashMap<K, V, S>, V, fn(&V) -> V);
 
-impl<K: Eq + Hash, V: Default> Indexed for DefaultMap<K, V> {
+impl<K, V: Default, S: Default> Default for DefaultMap<K, V, S> {
+    fn default() -> Self {
+        Self(HashMap::default(), V::default(), |_| V::default())
+    }
+}
+
+impl<K, V: Clone, S: Default> DefaultMap<K, V, S> {
+    /// Constructs an empty map in which every index has the given default priority.
+    pub fn with_default(default: V) -> Self {
+        Self(HashMap::default(), default, V::clone)
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher> Indexed for DefaultMap<K, V, S> {
     type Index = K;
     type Output = V;
 
@@ -14,22 +37,23 @@ impl<K: Eq + Hash, V: Default> Indexed for DefaultMap<K, V> {
     }
 
     fn get_mut(&mut self, index: Self::Index) -> Option<&mut Self::Output> {
-        Some(self.0.entry(index).or_default())
+        Some(self.0.entry(index).or_insert_with(|| (self.2)(&self.1)))
     }
 
     fn insert(&mut self, index: Self::Index, value: Self::Output) -> Option<Self::Output> {
         self.0.insert(index, value)
     }
 
+    /// Indices without a priority of their own yield the default priority.
     fn remove(&mut self, index: Self::Index) -> Option<Self::Output> {
-        self.0.remove(&index)
+        Some(self.0.remove(&index).unwrap_or_else(|| (self.2)(&self.1)))
     }
 
     fn clear(&mut self) {
         self.0.clear()
     }
 
-    fn iter(&mut self) -> impl Iterator<Item = &Self::Output> {
+    fn iter(&self) -> impl Iterator<Item = &Self::Output> {
         self.0.values()
     }
 
@@ -38,8 +62,36 @@ impl<K: Eq + Hash, V: Default> Indexed for DefaultMap<K, V> {
     }
 }
 
-impl<K, V: Default> From<HashMap<K, V>> for DefaultMap<K, V> {
-    fn from(value: HashMap<K, V>) -> Self {
-        Self(value, V::default())
+impl<K, V: Default, S> From<HashMap<K, V, S>> for DefaultMap<K, V, S> {
+    fn from(value: HashMap<K, V, S>) -> Self {
+        Self(value, V::default(), |_| V::default())
+    }
+}
+
+impl<Index, Priority, S, const D: usize, C, T, L>
+    IndexedPriorityQueue<
+        Index,
+        DefaultMap<Index, Priority, S>,
+        IndexedHashMap<Index, usize, S>,
+        D,
+        C,
+        T,
+        L,
+    >
+where
+    Index: Copy + Eq + Hash,
+    Priority: Clone,
+    S: BuildHasher + Default,
+    C: Compare<Priority>,
+    T: TieBreak,
+    L: Layout<Priority>,
+{
+    /// Constructs a new, empty queue in which every index has the given default priority.
+    pub fn with_default(default: Priority) -> Self
+    where
+        C: Default,
+        T: Default,
+    {
+        Self::new(DefaultMap::with_default(default), IndexedHashMap::default())
     }
 }
@@ -0,0 +1,351 @@
+use crate::compare::{Compare, MinOrder};
+use crate::indexed::{Indexed, PositionMap};
+use crate::layout::{Indirect, Layout};
+use crate::tie_break::{NoTieBreak, TieBreak};
+use crate::{IPQMutRefDown, IPQMutRefDyn, IPQMutRefUp, IndexedPriorityQueue};
+
+/// A view into a single index of an `IndexedPriorityQueue`.
+/// Created by [`IndexedPriorityQueue::entry`].
+pub enum Entry<
+    'a,
+    Index,
+    Priorities,
+    Positions,
+    const D: usize = 2,
+    C = MinOrder,
+    T = NoTieBreak,
+    L = Indirect,
+> where
+    Index: Copy,
+    Priorities: Indexed<Index = Index>,
+    C: Compare<Priorities::Output>,
+    T: TieBreak,
+    L: Layout<Priorities::Output>,
+    Positions: PositionMap<Priorities>,
+{
+    /// The index is in the queue.
+    Queued(QueuedEntry<'a, Index, Priorities, Positions, D, C, T, L>),
+    /// The index is not in the queue, but a priority is retained for it.
+    Retained(RetainedEntry<'a, Index, Priorities, Positions, D, C, T, L>),
+    /// The index is not in the queue and has no priority.
+    Vacant(VacantEntry<'a, Index, Priorities, Positions, D, C, T, L>),
+}
+
+/// A view into an index that is in the queue.
+pub struct QueuedEntry<
+    'a,
+    Index,
+    Priorities,
+    Positions,
+    const D: usize = 2,
+    C = MinOrder,
+    T = NoTieBreak,
+    L = Indirect,
+> where
+    Index: Copy,
+    Priorities: Indexed<Index = Index>,
+    C: Compare<Priorities::Output>,
+    T: TieBreak,
+    L: Layout<Priorities::Output>,
+    Positions: PositionMap<Priorities>,
+{
+    queue: &'a mut IndexedPriorityQueue<Index, Priorities, Positions, D, C, T, L>,
+    index: Index,
+    /// The heap index of the index.
+    position: usize,
+}
+
+/// A view into an index that is not in the queue, but has a retained priority.
+pub struct RetainedEntry<
+    'a,
+    Index,
+    Priorities,
+    Positions,
+    const D: usize = 2,
+    C = MinOrder,
+    T = NoTieBreak,
+    L = Indirect,
+> where
+    Index: Copy,
+    Priorities: Indexed<Index = Index>,
+    C: Compare<Priorities::Output>,
+    T: TieBreak,
+    L: Layout<Priorities::Output>,
+    Positions: PositionMap<Priorities>,
+{
+    queue: &'a mut IndexedPriorityQueue<Index, Priorities, Positions, D, C, T, L>,
+    index: Index,
+}
+
+/// A view into an index that is not in the queue and has no priority.
+pub struct VacantEntry<
+    'a,
+    Index,
+    Priorities,
+    Positions,
+    const D: usize = 2,
+    C = MinOrder,
+    T = NoTieBreak,
+    L = Indirect,
+> where
+    Index: Copy,
+    Priorities: Indexed<Index = Index>,
+    C: Compare<Priorities::Output>,
+    T: TieBreak,
+    L: Layout<Priorities::Output>,
+    Positions: PositionMap<Priorities>,
+{
+    queue: &'a mut IndexedPriorityQueue<Index, Priorities, Positions, D, C, T, L>,
+    index: Index,
+}
+
+impl<Index, Priorities, Positions, const D: usize, C, T, L>
+    IndexedPriorityQueue<Index, Priorities, Positions, D, C, T, L>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index>,
+    C: Compare<Priorities::Output>,
+    T: TieBreak,
+    L: Layout<Priorities::Output>,
+    Positions: PositionMap<Priorities>,
+{
+    /// Returns the entry for the specified index, which is either queued, retained or vacant.
+    /// Queued entries remember the heap index of the index, so acting on them does not look it up again.
+    ///
+    /// Time complexity: `O(1)`
+    pub fn entry(&mut self, index: Index) -> Entry<'_, Index, Priorities, Positions, D, C, T, L> {
+        match self.position(index) {
+            Some(position) => Entry::Queued(QueuedEntry {
+                queue: self,
+                index,
+                position,
+            }),
+            None if self.priorities.contains(index) => {
+                Entry::Retained(RetainedEntry { queue: self, index })
+            }
+            None => Entry::Vacant(VacantEntry { queue: self, index }),
+        }
+    }
+}
+
+impl<'a, Index, Priorities, Positions, const D: usize, C, T, L>
+    Entry<'a, Index, Priorities, Positions, D, C, T, L>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index>,
+    C: Compare<Priorities::Output>,
+    T: TieBreak,
+    L: Layout<Priorities::Output>,
+    Positions: PositionMap<Priorities>,
+{
+    /// Returns the index of this entry.
+    pub fn index(&self) -> Index {
+        match self {
+            Entry::Queued(entry) => entry.index,
+            Entry::Retained(entry) => entry.index,
+            Entry::Vacant(entry) => entry.index,
+        }
+    }
+
+    /// Inserts the index with the given priority into the queue if the entry is vacant.
+    /// Queued and retained entries are left untouched.
+    /// Returns the priority associated with the index.
+    ///
+    /// Time complexity: `O(log n)`
+    pub fn or_insert(self, priority: Priorities::Output) -> &'a Priorities::Output {
+        match self {
+            Entry::Queued(entry) => entry.into_priority(),
+            Entry::Retained(entry) => entry.into_priority(),
+            Entry::Vacant(entry) => entry.insert(priority).into_priority(),
+        }
+    }
+
+    /// Inserts the index with the priority computed by `f` into the queue if the entry is vacant.
+    /// Queued and retained entries are left untouched.
+    /// Returns the priority associated with the index.
+    ///
+    /// Time complexity: `O(log n)`
+    pub fn or_insert_with(self, f: impl FnOnce() -> Priorities::Output) -> &'a Priorities::Output {
+        match self {
+            Entry::Queued(entry) => entry.into_priority(),
+            Entry::Retained(entry) => entry.into_priority(),
+            Entry::Vacant(entry) => entry.insert(f()).into_priority(),
+        }
+    }
+}
+
+impl<'a, Index, Priorities, Positions, const D: usize, C, T, L>
+    QueuedEntry<'a, Index, Priorities, Positions, D, C, T, L>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index>,
+    C: Compare<Priorities::Output>,
+    T: TieBreak,
+    L: Layout<Priorities::Output>,
+    Positions: PositionMap<Priorities>,
+{
+    /// Returns the index of this entry.
+    pub fn index(&self) -> Index {
+        self.index
+    }
+
+    /// Returns the priority associated with the index.
+    pub fn priority(&self) -> &Priorities::Output {
+        self.queue.priority_at(self.position)
+    }
+
+    /// Converts the entry into a reference to the priority associated with the index.
+    pub fn into_priority(self) -> &'a Priorities::Output {
+        self.queue.priority_at(self.position)
+    }
+
+    /// Modifies the priority associated with the index, see [`IndexedPriorityQueue::update_dyn`].
+    ///
+    /// Time complexity: `O(log n)`
+    pub fn update_dyn(self) -> IPQMutRefDyn<'a, Index, Priorities, Positions, D, C, T, L> {
+        IPQMutRefDyn {
+            heap: self.queue,
+            index: self.index,
+            position: Some(self.position),
+        }
+    }
+
+    /// Increases the priority associated with the index, see [`IndexedPriorityQueue::update_up`].
+    ///
+    /// Time complexity: `O(log n)`
+    pub fn update_up(self) -> IPQMutRefUp<'a, Index, Priorities, Positions, D, C, T, L> {
+        IPQMutRefUp {
+            heap: self.queue,
+            index: self.index,
+            position: Some(self.position),
+        }
+    }
+
+    /// Decreases the priority associated with the index, see [`IndexedPriorityQueue::update_down`].
+    ///
+    /// Time complexity: `O(log n)`
+    pub fn update_down(self) -> IPQMutRefDown<'a, Index, Priorities, Positions, D, C, T, L> {
+        IPQMutRefDown {
+            heap: self.queue,
+            index: self.index,
+            position: Some(self.position),
+        }
+    }
+
+    /// Removes the index and its associated priority from the queue.
+    /// Returns the removed priority.
+    ///
+    /// Time complexity: `O(log n)`
+    pub fn remove(self) -> Priorities::Output {
+        self.queue.remove(self.index)
+    }
+
+    /// Removes the index from the queue, retaining its associated priority.
+    ///
+    /// Time complexity: `O(log n)`
+    pub fn remove_index(self) -> RetainedEntry<'a, Index, Priorities, Positions, D, C, T, L> {
+        self.queue.remove_index(self.index);
+        RetainedEntry {
+            queue: self.queue,
+            index: self.index,
+        }
+    }
+}
+
+impl<'a, Index, Priorities, Positions, const D: usize, C, T, L>
+    RetainedEntry<'a, Index, Priorities, Positions, D, C, T, L>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index>,
+    C: Compare<Priorities::Output>,
+    T: TieBreak,
+    L: Layout<Priorities::Output>,
+    Positions: PositionMap<Priorities>,
+{
+    /// Returns the index of this entry.
+    pub fn index(&self) -> Index {
+        self.index
+    }
+
+    /// Returns the priority retained for the index.
+    pub fn priority(&self) -> &Priorities::Output {
+        self.queue.priorities.index(self.index)
+    }
+
+    /// Returns a mutable reference to the priority retained for the index.
+    /// Since the index is not in the queue, the priority can be changed freely.
+    pub fn priority_mut(&mut self) -> &mut Priorities::Output {
+        self.queue.priorities.index_mut(self.index)
+    }
+
+    /// Converts the entry into a reference to the priority retained for the index.
+    pub fn into_priority(self) -> &'a Priorities::Output {
+        self.queue.priorities.index(self.index)
+    }
+
+    /// Reinserts the index into the queue with its retained priority.
+    ///
+    /// Time complexity: `O(log n)`
+    pub fn restore(self) -> QueuedEntry<'a, Index, Priorities, Positions, D, C, T, L> {
+        let position = self.queue.restore_index_at(self.index);
+        QueuedEntry {
+            queue: self.queue,
+            index: self.index,
+            position,
+        }
+    }
+
+    /// Reinserts the index into the queue with the given priority, replacing the retained priority.
+    ///
+    /// Time complexity: `O(log n)`
+    pub fn insert(
+        self,
+        priority: Priorities::Output,
+    ) -> QueuedEntry<'a, Index, Priorities, Positions, D, C, T, L> {
+        let (position, _) = self.queue.push_at(self.index, priority);
+        QueuedEntry {
+            queue: self.queue,
+            index: self.index,
+            position,
+        }
+    }
+
+    /// Removes the retained priority, making the entry vacant unless every index has a default priority.
+    /// Returns the removed priority.
+    ///
+    /// Time complexity: `O(1)`
+    pub fn forget(self) -> Priorities::Output {
+        self.queue.priorities.remove(self.index).unwrap()
+    }
+}
+
+impl<'a, Index, Priorities, Positions, const D: usize, C, T, L>
+    VacantEntry<'a, Index, Priorities, Positions, D, C, T, L>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index>,
+    C: Compare<Priorities::Output>,
+    T: TieBreak,
+    L: Layout<Priorities::Output>,
+    Positions: PositionMap<Priorities>,
+{
+    /// Returns the index of this entry.
+    pub fn index(&self) -> Index {
+        self.index
+    }
+
+    /// Inserts the index into the queue with the given priority.
+    ///
+    /// Time complexity: `O(log n)`
+    pub fn insert(
+        self,
+        priority: Priorities::Output,
+    ) -> QueuedEntry<'a, Index, Priorities, Positions, D, C, T, L> {
+        let (position, _) = self.queue.push_at(self.index, priority);
+        QueuedEntry {
+            queue: self.queue,
+            index: self.index,
+            position,
+        }
+    }
+}
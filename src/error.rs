@@ -0,0 +1,24 @@
+use std::fmt::{Display, Formatter};
+
+/// Errors returned by the fallible methods of `IndexedPriorityQueue`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// The index lies outside the range of indices supported by the backing maps.
+    OutOfRange,
+    /// The index has no associated priority.
+    MissingPriority,
+    /// The heap is too large for the position type of the positions map.
+    PositionOverflow,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::OutOfRange => write!(f, "index is out of range"),
+            Error::MissingPriority => write!(f, "index has no associated priority"),
+            Error::PositionOverflow => write!(f, "heap outgrew the position type"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -1,11 +1,19 @@
-use crate::indexed::Indexed;
+use crate::indexed::{Indexed, PositionMap};
+use std::collections::hash_map::Entry;
 use std::collections::HashMap;
-use std::hash::Hash;
+use std::hash::{BuildHasher, Hash, RandomState};
+use std::mem;
 
-#[derive(Default, Debug)]
-pub struct IndexedHashMap<K, V>(HashMap<K, V>);
+#[derive(Debug)]
+pub struct IndexedHashMap<K, V, S = RandomState>(HashMap<K, V, S>);
 
-impl<K: Eq + Hash, V> Indexed for IndexedHashMap<K, V> {
+impl<K, V, S: Default> Default for IndexedHashMap<K, V, S> {
+    fn default() -> Self {
+        Self(HashMap::default())
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher> Indexed for IndexedHashMap<K, V, S> {
     type Index = K;
     type Output = V;
 
@@ -29,7 +37,15 @@ impl<K: Eq + Hash, V> Indexed for IndexedHashMap<K, V> {
         self.0.clear()
     }
 
-    fn iter(&mut self) -> impl Iterator<Item = &Self::Output> {
+    fn get_or_insert_with(
+        &mut self,
+        index: Self::Index,
+        default: impl FnOnce() -> Self::Output,
+    ) -> &mut Self::Output {
+        self.0.entry(index).or_insert_with(default)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &Self::Output> {
         self.0.values()
     }
 
@@ -38,8 +54,161 @@ impl<K: Eq + Hash, V> Indexed for IndexedHashMap<K, V> {
     }
 }
 
-impl<K, V> From<HashMap<K, V>> for IndexedHashMap<K, V> {
-    fn from(value: HashMap<K, V>) -> Self {
+impl<K, V, S> From<HashMap<K, V, S>> for IndexedHashMap<K, V, S> {
+    fn from(value: HashMap<K, V, S>) -> Self {
         Self(value)
     }
 }
+
+/// Priorities map that stores the heap position of each index in the same entry as its priority,
+/// so that the queue finds both with a single hash lookup. Used together with [`Joint`].
+#[derive(Debug)]
+pub struct JointHashMap<K, P, S = RandomState>(HashMap<K, (P, Option<usize>), S>);
+
+impl<K, P, S: Default> Default for JointHashMap<K, P, S> {
+    fn default() -> Self {
+        Self(HashMap::default())
+    }
+}
+
+impl<K: Eq + Hash, P, S: BuildHasher> Indexed for JointHashMap<K, P, S> {
+    type Index = K;
+    type Output = P;
+
+    fn get(&self, index: Self::Index) -> Option<&Self::Output> {
+        self.0.get(&index).map(|(priority, _)| priority)
+    }
+
+    fn get_mut(&mut self, index: Self::Index) -> Option<&mut Self::Output> {
+        self.0.get_mut(&index).map(|(priority, _)| priority)
+    }
+
+    fn insert(&mut self, index: Self::Index, value: Self::Output) -> Option<Self::Output> {
+        match self.0.entry(index) {
+            Entry::Occupied(entry) => Some(mem::replace(&mut entry.into_mut().0, value)),
+            Entry::Vacant(entry) => {
+                entry.insert((value, None));
+                None
+            }
+        }
+    }
+
+    fn remove(&mut self, index: Self::Index) -> Option<Self::Output> {
+        self.0.remove(&index).map(|(priority, _)| priority)
+    }
+
+    fn clear(&mut self) {
+        self.0.clear()
+    }
+
+    fn get_or_insert_with(
+        &mut self,
+        index: Self::Index,
+        default: impl FnOnce() -> Self::Output,
+    ) -> &mut Self::Output {
+        &mut self.0.entry(index).or_insert_with(|| (default(), None)).0
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &Self::Output> {
+        self.0.values().map(|(priority, _)| priority)
+    }
+
+    fn iter_mut(&mut self) -> impl Iterator<Item = &mut Self::Output> {
+        self.0.values_mut().map(|(priority, _)| priority)
+    }
+}
+
+impl<K: Eq + Hash, P, S: BuildHasher + Default> From<HashMap<K, P, S>> for JointHashMap<K, P, S> {
+    fn from(value: HashMap<K, P, S>) -> Self {
+        Self(
+            value
+                .into_iter()
+                .map(|(index, priority)| (index, (priority, None)))
+                .collect(),
+        )
+    }
+}
+
+/// Position map that keeps the positions inside a [`JointHashMap`] instead of a map of its own.
+///
+/// Only indices with a priority can be on the heap, so positions are removed along with
+/// the priorities.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Joint;
+
+impl<K: Eq + Hash, P, S: BuildHasher> PositionMap<JointHashMap<K, P, S>> for Joint {
+    const IN_PRIORITIES: bool = true;
+
+    fn position(&self, priorities: &JointHashMap<K, P, S>, index: K) -> Option<usize> {
+        priorities.0.get(&index)?.1
+    }
+
+    fn set_position(&mut self, priorities: &mut JointHashMap<K, P, S>, index: K, position: usize) {
+        priorities.0.get_mut(&index).unwrap().1 = Some(position);
+    }
+
+    fn insert_position(
+        &mut self,
+        priorities: &mut JointHashMap<K, P, S>,
+        index: K,
+        position: usize,
+    ) -> Option<usize> {
+        let current = &mut priorities.0.get_mut(&index).unwrap().1;
+        if current.is_none() {
+            *current = Some(position);
+            None
+        } else {
+            *current
+        }
+    }
+
+    fn remove_position(
+        &mut self,
+        priorities: &mut JointHashMap<K, P, S>,
+        index: K,
+    ) -> Option<usize> {
+        priorities.0.get_mut(&index)?.1.take()
+    }
+
+    fn clear_positions(&mut self, priorities: &mut JointHashMap<K, P, S>, indices: &[K]) {
+        for index in indices {
+            if let Some((_, position)) = priorities.0.get_mut(index) {
+                *position = None;
+            }
+        }
+    }
+
+    fn count_positions(&self, priorities: &JointHashMap<K, P, S>) -> usize {
+        priorities
+            .0
+            .values()
+            .filter(|(_, position)| position.is_some())
+            .count()
+    }
+
+    fn push_priority(
+        &mut self,
+        priorities: &mut JointHashMap<K, P, S>,
+        index: K,
+        value: P,
+        position: usize,
+    ) -> (Option<usize>, Option<P>) {
+        match priorities.0.entry(index) {
+            Entry::Occupied(entry) => {
+                let (priority, current) = entry.into_mut();
+                let old_priority = Some(mem::replace(priority, value));
+                match current {
+                    Some(current) => (Some(*current), old_priority),
+                    None => {
+                        *current = Some(position);
+                        (None, old_priority)
+                    }
+                }
+            }
+            Entry::Vacant(entry) => {
+                entry.insert((value, Some(position)));
+                (None, None)
+            }
+        }
+    }
+}
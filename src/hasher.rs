@@ -0,0 +1,67 @@
+use std::hash::{BuildHasherDefault, DefaultHasher, Hasher};
+
+/// Builds [`IntegerHasher`]s, for hash maps with integer keys.
+pub type BuildIntegerHasher = BuildHasherDefault<IntegerHasher>;
+
+/// Builds SipHash hashers with fixed keys, for hash maps whose iteration order must be the same
+/// on every run, such as in reproducible tests.
+///
+/// The hashes are only stable for a given version of the standard library.
+pub type DeterministicState = BuildHasherDefault<DefaultHasher>;
+
+/// Multiplicative hasher that consumes a machine word in a single step, following the hasher of the
+/// Firefox and rustc hash maps.
+///
+/// Much faster than the default SipHash for integer keys such as dense node ids,
+/// but it offers no protection against keys chosen to collide.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct IntegerHasher(u64);
+
+impl IntegerHasher {
+    const SEED: u64 = 0xf135_7aea_2e62_a9c5;
+
+    fn add(&mut self, word: u64) {
+        self.0 = (self.0 ^ word).wrapping_mul(Self::SEED);
+    }
+}
+
+impl Hasher for IntegerHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for chunk in bytes.chunks(8) {
+            let mut word = [0; 8];
+            word[..chunk.len()].copy_from_slice(chunk);
+            self.add(u64::from_le_bytes(word));
+        }
+    }
+
+    fn write_u8(&mut self, i: u8) {
+        self.add(i as u64);
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.add(i as u64);
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.add(i as u64);
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.add(i);
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.add(i as u64);
+        self.add((i >> 64) as u64);
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.add(i as u64);
+    }
+
+    /// The hash map takes the bucket from the low bits and the tag from the high bits,
+    /// so the well-mixed high bits of the product are rotated into the low bits.
+    fn finish(&self) -> u64 {
+        self.0.rotate_left(26)
+    }
+}
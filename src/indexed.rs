@@ -1,3 +1,5 @@
+use std::mem;
+
 pub trait Indexed {
     type Index;
     type Output;
@@ -8,6 +10,12 @@ pub trait Indexed {
     fn remove(&mut self, index: Self::Index) -> Option<Self::Output>;
     fn clear(&mut self);
 
+    /// Returns `false` if the index lies outside the range of indices this map can store.
+    /// Methods other than `get`, `get_mut` and `contains` may panic for such indices.
+    fn in_range(&self, _index: Self::Index) -> bool {
+        true
+    }
+
     fn contains(&self, index: Self::Index) -> bool {
         self.get(index).is_some()
     }
@@ -20,6 +28,206 @@ pub trait Indexed {
         self.get_mut(index).unwrap()
     }
 
-    fn iter(&mut self) -> impl Iterator<Item = &Self::Output>;
+    /// Returns a mutable reference to the value of the index, first inserting the value returned by
+    /// `default` if the index has none. Maps should override this to look up the index only once.
+    fn get_or_insert_with(
+        &mut self,
+        index: Self::Index,
+        default: impl FnOnce() -> Self::Output,
+    ) -> &mut Self::Output
+    where
+        Self::Index: Copy,
+    {
+        if !self.contains(index) {
+            self.insert(index, default());
+        }
+        self.index_mut(index)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &Self::Output>;
     fn iter_mut(&mut self) -> impl Iterator<Item = &mut Self::Output>;
 }
+
+/// Unsigned integer type of the positions stored in a positions map.
+///
+/// Narrower types save memory for large queues, but limit the number of indices on the heap
+/// to `MAX - 1`, since `MAX` is reserved as the sentinel for absent positions.
+pub trait Position: Copy + Eq {
+    /// The sentinel for absent positions, which is never a valid position.
+    const NONE: Self;
+
+    /// Converts a heap position, or returns `None` if it does not fit into this type.
+    fn from_usize(position: usize) -> Option<Self>;
+
+    /// Converts this position back into a heap position.
+    fn to_usize(self) -> usize;
+}
+
+macro_rules! impl_position {
+    ($($t:ty),*) => {
+        $(
+            impl Position for $t {
+                const NONE: Self = <$t>::MAX;
+
+                fn from_usize(position: usize) -> Option<Self> {
+                    <$t>::try_from(position).ok().filter(|position| *position != Self::NONE)
+                }
+
+                fn to_usize(self) -> usize {
+                    self as usize
+                }
+            }
+        )*
+    };
+}
+
+impl_position!(u16, u32, u64, usize);
+
+/// Storage for the heap positions of the indices of an [`IndexedPriorityQueue`](crate::IndexedPriorityQueue).
+///
+/// Every `Indexed` map of [`Position`]s is a position map, which panics when the heap outgrows the position type. Position maps may also store the positions
+/// inside the priorities map, such as [`Joint`](crate::hash_map::Joint), which is why all methods
+/// receive the priorities map as well.
+pub trait PositionMap<Priorities: Indexed> {
+    /// `true` if the positions are stored in the entries of the priorities map, which then has to
+    /// keep the priorities of the indices on the heap.
+    const IN_PRIORITIES: bool = false;
+
+    /// Returns the position of the index, or `None` if it is not on the heap.
+    fn position(&self, priorities: &Priorities, index: Priorities::Index) -> Option<usize>;
+
+    /// Sets the position of an index that is on the heap.
+    fn set_position(
+        &mut self,
+        priorities: &mut Priorities,
+        index: Priorities::Index,
+        position: usize,
+    );
+
+    /// Sets the position of the index to `position` if it is not on the heap.
+    /// Returns the current position otherwise.
+    fn insert_position(
+        &mut self,
+        priorities: &mut Priorities,
+        index: Priorities::Index,
+        position: usize,
+    ) -> Option<usize>;
+
+    /// Removes the index from the heap. Returns its position, if it was on the heap.
+    fn remove_position(
+        &mut self,
+        priorities: &mut Priorities,
+        index: Priorities::Index,
+    ) -> Option<usize>;
+
+    /// Removes all indices from the heap, which are given by `indices`.
+    fn clear_positions(&mut self, priorities: &mut Priorities, indices: &[Priorities::Index]);
+
+    /// Returns the number of indices on the heap.
+    fn count_positions(&self, priorities: &Priorities) -> usize;
+
+    /// Returns `false` if the index lies outside the range of indices this map can store.
+    fn position_in_range(&self, _index: Priorities::Index) -> bool {
+        true
+    }
+
+    /// Returns `false` if the heap position cannot be stored by this map.
+    fn position_fits(&self, _position: usize) -> bool {
+        true
+    }
+
+    /// Associates the priority with the index. If the index is on the heap, returns its position
+    /// and the replaced priority. Otherwise, sets its position to `position` and returns `None`
+    /// and the previous priority, if it had one.
+    fn push_priority(
+        &mut self,
+        priorities: &mut Priorities,
+        index: Priorities::Index,
+        value: Priorities::Output,
+        position: usize,
+    ) -> (Option<usize>, Option<Priorities::Output>);
+}
+
+impl<Priorities, Positions> PositionMap<Priorities> for Positions
+where
+    Priorities: Indexed<Index: Copy>,
+    Positions: Indexed<Index = Priorities::Index, Output: Position>,
+{
+    fn position(&self, _priorities: &Priorities, index: Priorities::Index) -> Option<usize> {
+        self.get(index).map(|position| position.to_usize())
+    }
+
+    fn set_position(
+        &mut self,
+        _priorities: &mut Priorities,
+        index: Priorities::Index,
+        position: usize,
+    ) {
+        *self.index_mut(index) = to_position(position);
+    }
+
+    fn insert_position(
+        &mut self,
+        _priorities: &mut Priorities,
+        index: Priorities::Index,
+        position: usize,
+    ) -> Option<usize> {
+        let mut inserted = false;
+        let current = *self.get_or_insert_with(index, || {
+            inserted = true;
+            to_position(position)
+        });
+        (!inserted).then(|| current.to_usize())
+    }
+
+    fn remove_position(
+        &mut self,
+        _priorities: &mut Priorities,
+        index: Priorities::Index,
+    ) -> Option<usize> {
+        self.remove(index).map(|position| position.to_usize())
+    }
+
+    fn clear_positions(&mut self, _priorities: &mut Priorities, indices: &[Priorities::Index]) {
+        for &index in indices {
+            self.remove(index);
+        }
+    }
+
+    fn count_positions(&self, _priorities: &Priorities) -> usize {
+        self.iter().count()
+    }
+
+    fn position_in_range(&self, index: Priorities::Index) -> bool {
+        self.in_range(index)
+    }
+
+    fn position_fits(&self, position: usize) -> bool {
+        Positions::Output::from_usize(position).is_some()
+    }
+
+    fn push_priority(
+        &mut self,
+        priorities: &mut Priorities,
+        index: Priorities::Index,
+        value: Priorities::Output,
+        position: usize,
+    ) -> (Option<usize>, Option<Priorities::Output>) {
+        match self.insert_position(priorities, index, position) {
+            Some(current) => (
+                Some(current),
+                Some(mem::replace(priorities.index_mut(index), value)),
+            ),
+            None => (None, priorities.insert(index, value)),
+        }
+    }
+}
+
+/// Converts a heap position into the position type of a map.
+///
+/// # Panics
+///
+/// Panics if the heap outgrew the position type.
+fn to_position<P: Position>(position: usize) -> P {
+    P::from_usize(position).expect("heap outgrew the position type")
+}
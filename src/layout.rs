@@ -0,0 +1,173 @@
+use crate::indexed::{Indexed, PositionMap};
+use std::mem;
+
+/// Storage layout of the heap of an [`IndexedPriorityQueue`](crate::IndexedPriorityQueue).
+///
+/// The layout decides what is stored next to each index on the heap, and thus whether the
+/// priorities of the indices on the heap are kept in the priorities map or on the heap itself.
+/// Priorities of indices that are not on the heap are always kept in the priorities map.
+pub trait Layout<P> {
+    /// `true` if the priorities of the indices on the heap are stored on the heap,
+    /// and thus missing from the priorities map.
+    const STORES_PRIORITIES: bool;
+
+    /// The data stored next to each index on the heap.
+    type Stored;
+
+    /// Returns the data to store next to an index that is reinserted into the heap,
+    /// moving its priority out of the priorities map if the layout stores it.
+    fn restore<M: Indexed<Output = P>>(priorities: &mut M, index: M::Index) -> Self::Stored;
+
+    /// Takes the data stored next to an index that is removed from the heap,
+    /// moving its priority back into the priorities map if the layout stores it.
+    fn retain<M: Indexed<Output = P>>(priorities: &mut M, index: M::Index, stored: Self::Stored);
+
+    /// Takes the data stored next to an index that is removed from the heap together with its
+    /// priority. Returns the priority, wherever it was stored.
+    fn remove<M: Indexed<Output = P>>(
+        priorities: &mut M,
+        index: M::Index,
+        stored: Self::Stored,
+    ) -> Option<P>;
+
+    /// Associates the priority with the index, like [`PositionMap::push_priority`],
+    /// and pushes the data to store next to the index onto `stored` if it was not on the heap.
+    fn push_priority<M, Positions>(
+        positions: &mut Positions,
+        priorities: &mut M,
+        stored: &mut Vec<Self::Stored>,
+        index: M::Index,
+        value: P,
+        position: usize,
+    ) -> (Option<usize>, Option<P>)
+    where
+        M: Indexed<Index: Copy, Output = P>,
+        Positions: PositionMap<M>;
+
+    /// Returns the priority stored next to an index, or `None` if it is kept in the priorities map.
+    fn get(stored: &Self::Stored) -> Option<&P>;
+
+    /// Returns a mutable reference to the priority stored next to an index, or `None` if it is
+    /// kept in the priorities map.
+    fn get_mut(stored: &mut Self::Stored) -> Option<&mut P>;
+}
+
+/// Stores only the indices on the heap, and looks up their priorities in the priorities map
+/// on every comparison. This is the default layout.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Indirect;
+
+impl<P> Layout<P> for Indirect {
+    const STORES_PRIORITIES: bool = false;
+
+    type Stored = ();
+
+    fn restore<M: Indexed<Output = P>>(_priorities: &mut M, _index: M::Index) -> Self::Stored {}
+
+    fn retain<M: Indexed<Output = P>>(
+        _priorities: &mut M,
+        _index: M::Index,
+        _stored: Self::Stored,
+    ) {
+    }
+
+    fn remove<M: Indexed<Output = P>>(
+        priorities: &mut M,
+        index: M::Index,
+        _stored: Self::Stored,
+    ) -> Option<P> {
+        priorities.remove(index)
+    }
+
+    fn push_priority<M, Positions>(
+        positions: &mut Positions,
+        priorities: &mut M,
+        stored: &mut Vec<Self::Stored>,
+        index: M::Index,
+        value: P,
+        position: usize,
+    ) -> (Option<usize>, Option<P>)
+    where
+        M: Indexed<Index: Copy, Output = P>,
+        Positions: PositionMap<M>,
+    {
+        let (current, old_priority) = positions.push_priority(priorities, index, value, position);
+        if current.is_none() {
+            stored.push(());
+        }
+        (current, old_priority)
+    }
+
+    fn get(_stored: &Self::Stored) -> Option<&P> {
+        None
+    }
+
+    fn get_mut(_stored: &mut Self::Stored) -> Option<&mut P> {
+        None
+    }
+}
+
+/// Stores the priorities of the indices on the heap next to them, so that comparisons read
+/// contiguous memory instead of probing the priorities map.
+///
+/// Priorities move into the heap when their index is pushed or restored, and back into the
+/// priorities map when it is removed or popped, so the map only holds the retained priorities.
+/// Pays off for maps with expensive lookups, such as [`IndexedHashMap`](crate::hash_map::IndexedHashMap).
+/// Cannot be combined with position maps that are stored in the priorities map, such as
+/// [`Joint`](crate::hash_map::Joint).
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Inline;
+
+impl<P> Layout<P> for Inline {
+    const STORES_PRIORITIES: bool = true;
+
+    type Stored = P;
+
+    fn restore<M: Indexed<Output = P>>(priorities: &mut M, index: M::Index) -> Self::Stored {
+        priorities.remove(index).unwrap()
+    }
+
+    fn retain<M: Indexed<Output = P>>(priorities: &mut M, index: M::Index, stored: Self::Stored) {
+        priorities.insert(index, stored);
+    }
+
+    fn remove<M: Indexed<Output = P>>(
+        _priorities: &mut M,
+        _index: M::Index,
+        stored: Self::Stored,
+    ) -> Option<P> {
+        Some(stored)
+    }
+
+    fn push_priority<M, Positions>(
+        positions: &mut Positions,
+        priorities: &mut M,
+        stored: &mut Vec<Self::Stored>,
+        index: M::Index,
+        value: P,
+        position: usize,
+    ) -> (Option<usize>, Option<P>)
+    where
+        M: Indexed<Index: Copy, Output = P>,
+        Positions: PositionMap<M>,
+    {
+        match positions.insert_position(priorities, index, position) {
+            Some(current) => (
+                Some(current),
+                Some(mem::replace(&mut stored[current], value)),
+            ),
+            None => {
+                stored.push(value);
+                (None, priorities.remove(index))
+            }
+        }
+    }
+
+    fn get(stored: &Self::Stored) -> Option<&P> {
+        Some(stored)
+    }
+
+    fn get_mut(stored: &mut Self::Stored) -> Option<&mut P> {
+        Some(stored)
+    }
+}
@@ -1,31 +1,175 @@
-mod array_map;
-mod default_map;
-mod hash_map;
+pub mod array_map;
+pub mod btree_map;
+pub mod bucket;
+pub mod compare;
+pub mod default_map;
+pub mod entry;
+mod error;
+pub mod hash_map;
+pub mod hasher;
 pub mod indexed;
+pub mod layout;
+pub mod min_max;
+pub mod pairing;
+pub mod radix;
+mod slots;
+pub mod tie_break;
+mod validate;
+pub mod vec_map;
 
-use crate::array_map::{ArrayPositionMap, ArrayPriorityMap};
+use crate::array_map::{ArrayPositionMap, ArrayPriorityMap, DefaultArrayMap, IndexMapper, Offset};
+use crate::btree_map::IndexedBTreeMap;
+use crate::bucket::BucketIPQ;
+use crate::compare::{Compare, MaxOrder, MinOrder};
 use crate::default_map::DefaultMap;
-use crate::hash_map::IndexedHashMap;
-use crate::indexed::Indexed;
+use crate::hash_map::{IndexedHashMap, Joint, JointHashMap};
+use crate::indexed::{Indexed, PositionMap};
+use crate::layout::{Indirect, Inline, Layout};
+use crate::min_max::IndexedMinMaxQueue;
+use crate::pairing::PairingIPQ;
+use crate::radix::RadixIPQ;
+use crate::tie_break::{NoTieBreak, TieBreak};
+use crate::vec_map::{VecPositionMap, VecPriorityMap};
+pub use error::Error;
 use std::cmp::Ordering;
-use std::ops::{Deref, DerefMut};
+use std::hash::RandomState;
+use std::ops::{Deref, DerefMut, Range};
+use std::{slice, vec};
+pub use validate::InvariantViolation;
 
-pub type HashMapIPQ<Index, Priority> =
-    IndexedPriorityQueue<Index, IndexedHashMap<Index, Priority>, IndexedHashMap<Index, usize>>;
+pub type HashMapIPQ<Index, Priority, S = RandomState> = IndexedPriorityQueue<
+    Index,
+    IndexedHashMap<Index, Priority, S>,
+    IndexedHashMap<Index, usize, S>,
+>;
 
-pub type DefaultMapIPQ<Index, Priority> =
-    IndexedPriorityQueue<Index, DefaultMap<Index, Priority>, IndexedHashMap<Index, usize>>;
+pub type BTreeMapIPQ<Index, Priority> =
+    IndexedPriorityQueue<Index, IndexedBTreeMap<Index, Priority>, IndexedBTreeMap<Index, usize>>;
 
-pub type ArrayMapIPQ<Priority, const OFFSET: usize = 0> =
-    IndexedPriorityQueue<usize, ArrayPriorityMap<Priority, OFFSET>, ArrayPositionMap<OFFSET>>;
+pub type DefaultMapIPQ<Index, Priority, S = RandomState> =
+    IndexedPriorityQueue<Index, DefaultMap<Index, Priority, S>, IndexedHashMap<Index, usize, S>>;
+
+pub type ArrayMapIPQ<Priority, const OFFSET: usize = 0> = IndexedPriorityQueue<
+    usize,
+    ArrayPriorityMap<Priority, Offset<OFFSET>>,
+    ArrayPositionMap<Offset<OFFSET>>,
+>;
+
+pub type VecMapIPQ<Priority, const OFFSET: usize = 0> =
+    IndexedPriorityQueue<usize, VecPriorityMap<Priority, OFFSET>, VecPositionMap<OFFSET>>;
+
+pub type MaxHashMapIPQ<Index, Priority, S = RandomState> = IndexedPriorityQueue<
+    Index,
+    IndexedHashMap<Index, Priority, S>,
+    IndexedHashMap<Index, usize, S>,
+    2,
+    MaxOrder,
+>;
+
+pub type CompactArrayMapIPQ<Priority, const OFFSET: usize = 0> = IndexedPriorityQueue<
+    usize,
+    ArrayPriorityMap<Priority, Offset<OFFSET>>,
+    ArrayPositionMap<Offset<OFFSET>, u32>,
+>;
+
+pub type MappedArrayMapIPQ<Priority, M> = IndexedPriorityQueue<
+    <M as IndexMapper>::Index,
+    ArrayPriorityMap<Priority, M>,
+    ArrayPositionMap<M>,
+>;
+
+pub type DefaultArrayMapIPQ<Priority, const OFFSET: usize = 0> = IndexedPriorityQueue<
+    usize,
+    DefaultArrayMap<Priority, Offset<OFFSET>>,
+    ArrayPositionMap<Offset<OFFSET>>,
+>;
+
+pub type MaxArrayMapIPQ<Priority, const OFFSET: usize = 0> = IndexedPriorityQueue<
+    usize,
+    ArrayPriorityMap<Priority, Offset<OFFSET>>,
+    ArrayPositionMap<Offset<OFFSET>>,
+    2,
+    MaxOrder,
+>;
+
+pub type MaxVecMapIPQ<Priority, const OFFSET: usize = 0> = IndexedPriorityQueue<
+    usize,
+    VecPriorityMap<Priority, OFFSET>,
+    VecPositionMap<OFFSET>,
+    2,
+    MaxOrder,
+>;
+
+pub type InlineHashMapIPQ<Index, Priority, S = RandomState> = IndexedPriorityQueue<
+    Index,
+    IndexedHashMap<Index, Priority, S>,
+    IndexedHashMap<Index, usize, S>,
+    2,
+    MinOrder,
+    NoTieBreak,
+    Inline,
+>;
+
+pub type JointHashMapIPQ<Index, Priority, S = RandomState> =
+    IndexedPriorityQueue<Index, JointHashMap<Index, Priority, S>, Joint>;
+
+pub type HashMapPairingIPQ<Index, Priority, S = RandomState> =
+    PairingIPQ<Index, IndexedHashMap<Index, Priority, S>, IndexedHashMap<Index, usize, S>>;
+
+pub type ArrayMapPairingIPQ<Priority, const OFFSET: usize = 0> =
+    PairingIPQ<usize, ArrayPriorityMap<Priority, Offset<OFFSET>>, ArrayPositionMap<Offset<OFFSET>>>;
+
+pub type HashMapRadixIPQ<Index, Priority, S = RandomState> =
+    RadixIPQ<Index, IndexedHashMap<Index, Priority, S>, IndexedHashMap<Index, usize, S>>;
+
+pub type ArrayMapRadixIPQ<Priority, const OFFSET: usize = 0> =
+    RadixIPQ<usize, ArrayPriorityMap<Priority, Offset<OFFSET>>, ArrayPositionMap<Offset<OFFSET>>>;
+
+pub type HashMapBucketIPQ<Index, S = RandomState> =
+    BucketIPQ<Index, IndexedHashMap<Index, usize, S>, IndexedHashMap<Index, usize, S>>;
+
+pub type ArrayMapBucketIPQ<const OFFSET: usize = 0> =
+    BucketIPQ<usize, ArrayPriorityMap<usize, Offset<OFFSET>>, ArrayPositionMap<Offset<OFFSET>>>;
+
+pub type HashMapMinMaxQueue<Index, Priority, S = RandomState> =
+    IndexedMinMaxQueue<Index, IndexedHashMap<Index, Priority, S>, IndexedHashMap<Index, usize, S>>;
+
+pub type ArrayMapMinMaxQueue<Priority, const OFFSET: usize = 0> = IndexedMinMaxQueue<
+    usize,
+    ArrayPriorityMap<Priority, Offset<OFFSET>>,
+    ArrayPositionMap<Offset<OFFSET>>,
+>;
 
 /// Indexed Priority Queue.
+///
+/// The queue is backed by a `D`-ary heap, where `D` is the number of children of each node.
+/// Higher arities make the heap shallower, which speeds up `up_heap` (and thus `update_down`)
+/// at the cost of more comparisons per level in `down_heap`.
+///
+/// Priorities are ordered by the comparator `C`, which pops the smallest priority first by default.
+/// Throughout this crate, "smaller" and "larger" refer to the order defined by the comparator.
+///
+/// Indices with equal priorities are ordered by the tie-breaking policy `T`, which leaves their order
+/// unspecified by default.
+///
+/// The layout `L` decides whether the heap stores the priorities of its indices next to them,
+/// see [`Inline`], or only the indices, see [`Indirect`].
 #[derive(Debug)]
-pub struct IndexedPriorityQueue<Index, Priorities, Positions>
-where
+pub struct IndexedPriorityQueue<
+    Index,
+    Priorities,
+    Positions,
+    const D: usize = 2,
+    C = MinOrder,
+    T = NoTieBreak,
+    L = Indirect,
+> where
     Index: Copy,
-    Priorities: Indexed<Index = Index, Output: Ord + Clone>,
-    Positions: Indexed<Index = Index, Output = usize>,
+    Priorities: Indexed<Index = Index>,
+    C: Compare<Priorities::Output>,
+    T: TieBreak,
+    L: Layout<Priorities::Output>,
+    Positions: PositionMap<Priorities>,
 {
     /// The priorities associated with indexes on the heap.
     priorities: Priorities,
@@ -33,16 +177,32 @@ where
     positions: Positions,
     /// The underlying vec storing the indexes in order of priority.
     heap: Vec<Index>,
+    /// The comparator defining the order of the priorities.
+    compare: C,
+    /// The tie-breaking policy defining the order of equal priorities.
+    tie_break: T,
+    /// The tie-breaking stamps of the indexes on the heap, stored in the same order as `heap`.
+    stamps: Vec<T::Stamp>,
+    /// The data stored by the layout `L` for the indexes on the heap, stored in the same order as `heap`.
+    stored: Vec<L::Stored>,
 }
 
-impl<Index, Priorities, Positions> IndexedPriorityQueue<Index, Priorities, Positions>
+impl<Index, Priorities, Positions, const D: usize, C, T, L>
+    IndexedPriorityQueue<Index, Priorities, Positions, D, C, T, L>
 where
     Index: Copy,
-    Priorities: Indexed<Index = Index, Output: Ord + Clone>,
-    Positions: Indexed<Index = Index, Output = usize>,
+    Priorities: Indexed<Index = Index>,
+    C: Compare<Priorities::Output>,
+    T: TieBreak,
+    L: Layout<Priorities::Output>,
+    Positions: PositionMap<Priorities>,
 {
     /// Constructs a new, empty `IndexedPriorityQueue`.
-    pub fn new(priorities: impl Into<Priorities>, positions: impl Into<Positions>) -> Self {
+    pub fn new(priorities: impl Into<Priorities>, positions: impl Into<Positions>) -> Self
+    where
+        C: Default,
+        T: Default,
+    {
         Self::with_capacity(priorities.into(), positions.into(), 0)
     }
 
@@ -51,14 +211,116 @@ where
         priorities: impl Into<Priorities>,
         positions: impl Into<Positions>,
         capacity: usize,
+    ) -> Self
+    where
+        C: Default,
+        T: Default,
+    {
+        Self::with_capacity_and_comparator(priorities, positions, capacity, C::default())
+    }
+
+    /// Constructs a new, empty `IndexedPriorityQueue` that orders priorities using the given comparator.
+    pub fn with_comparator(
+        priorities: impl Into<Priorities>,
+        positions: impl Into<Positions>,
+        compare: C,
+    ) -> Self
+    where
+        T: Default,
+    {
+        Self::with_capacity_and_comparator(priorities, positions, 0, compare)
+    }
+
+    /// Constructs a new, empty `IndexedPriorityQueue` with at least the specified capacity,
+    /// that orders priorities using the given comparator.
+    pub fn with_capacity_and_comparator(
+        priorities: impl Into<Priorities>,
+        positions: impl Into<Positions>,
+        capacity: usize,
+        compare: C,
+    ) -> Self
+    where
+        T: Default,
+    {
+        Self::from_parts(priorities, positions, capacity, compare, T::default())
+    }
+
+    /// Constructs a new, empty `IndexedPriorityQueue` that orders equal priorities using the given
+    /// tie-breaking policy.
+    pub fn with_tie_break(
+        priorities: impl Into<Priorities>,
+        positions: impl Into<Positions>,
+        tie_break: T,
+    ) -> Self
+    where
+        C: Default,
+    {
+        Self::from_parts(priorities, positions, 0, C::default(), tie_break)
+    }
+
+    /// Constructs a new, empty `IndexedPriorityQueue` that orders priorities using the given comparator
+    /// and equal priorities using the given tie-breaking policy.
+    pub fn with_comparator_and_tie_break(
+        priorities: impl Into<Priorities>,
+        positions: impl Into<Positions>,
+        compare: C,
+        tie_break: T,
     ) -> Self {
+        Self::from_parts(priorities, positions, 0, compare, tie_break)
+    }
+
+    /// Constructs a new, empty `IndexedPriorityQueue` from all of its parts.
+    fn from_parts(
+        priorities: impl Into<Priorities>,
+        positions: impl Into<Positions>,
+        capacity: usize,
+        compare: C,
+        tie_break: T,
+    ) -> Self {
+        const { assert!(D >= 2, "the arity of the heap must be at least 2") };
+        const {
+            assert!(
+                !(L::STORES_PRIORITIES && Positions::IN_PRIORITIES),
+                "the layout cannot store the priorities of a priorities map that stores the positions"
+            )
+        };
+
         Self {
             priorities: priorities.into(),
             positions: positions.into(),
             heap: Vec::with_capacity(capacity),
+            compare,
+            tie_break,
+            stamps: Vec::with_capacity(capacity),
+            stored: Vec::with_capacity(capacity),
         }
     }
 
+    /// Constructs a new `IndexedPriorityQueue` containing the given index-priority pairs.
+    /// If an index occurs multiple times, the last priority associated with it is kept.
+    ///
+    /// Time complexity: `O(n)`
+    pub fn heapify(
+        priorities: impl Into<Priorities>,
+        positions: impl Into<Positions>,
+        pairs: impl IntoIterator<Item = (Index, Priorities::Output)>,
+    ) -> Self
+    where
+        C: Default,
+        T: Default,
+    {
+        let pairs = pairs.into_iter();
+        let mut queue = Self::with_capacity(priorities, positions, pairs.size_hint().0);
+
+        for (index, priority) in pairs {
+            queue.push_unordered(index, priority);
+        }
+        queue.rebuild();
+        queue.check_invariants();
+
+        queue
+    }
+
     /// Returns the number of indices in the indexed priority queue.
     ///
     /// Time complexity: `O(1)`
@@ -77,58 +339,103 @@ where
     ///
     /// Time complexity: `O(1)`
     pub fn contains(&self, index: Index) -> bool {
-        self.positions.contains(index)
+        self.position(index).is_some()
     }
 
     /// Returns the smallest priority in the queue, or `None`, if it is empty.
     ///
     /// Time complexity: `O(1)`
     pub fn min_priority(&self) -> Option<&Priorities::Output> {
-        self.heap
-            .first()
-            .and_then(|index| self.priorities.get(*index))
+        (!self.is_empty()).then(|| self.priority_at(0))
     }
 
     /// Returns the priority associated with the specified index, or `None`, if the index is not in the queue.
     ///
     /// Time complexity: `O(1)`
     pub fn get_priority(&self, index: Index) -> Option<&Priorities::Output> {
+        if L::STORES_PRIORITIES {
+            if let Some(position) = self.position(index) {
+                return Some(self.priority_at(position));
+            }
+        }
         self.priorities.get(index)
     }
 
+    /// Returns an iterator over the indices in the queue and their priorities, in arbitrary order.
+    pub fn iter(&self) -> Iter<'_, Index, Priorities, L> {
+        Iter {
+            indices: self.heap.iter(),
+            stored: self.stored.iter(),
+            priorities: &self.priorities,
+        }
+    }
+
+    /// Returns an iterator over the indices in the queue, in the order in which they are stored on the heap.
+    /// The first index is the index associated with the smallest priority.
+    pub fn indices(&self) -> slice::Iter<'_, Index> {
+        self.heap.iter()
+    }
+
     /// Reinserts a previously removed index into the queue with its last associated value.
+    /// Returns an error if the index is out of range, has no associated value,
+    /// or the heap would outgrow the position type.
     ///
     /// Time complexity: `O(log n)`
-    pub fn restore_index(&mut self, index: Index) {
-        if !self.positions.contains(index) {
-            let position = self.len();
-            self.heap.push(index);
-            self.positions.insert(index, position);
-            self.up_heap(position);
+    pub fn try_restore_index(&mut self, index: Index) -> Result<(), Error> {
+        self.check_range(index)?;
+        if !self.has_priority(index) {
+            return Err(Error::MissingPriority);
         }
+        self.check_growth(index)?;
+        self.restore_index(index);
+        Ok(())
+    }
+
+    /// Reinserts a previously removed index into the queue with its last associated value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the heap outgrows the position type of the positions map.
+    ///
+    /// Time complexity: `O(log n)`
+    pub fn restore_index(&mut self, index: Index) {
+        self.restore_index_at(index);
     }
 
     /// Removes the specified index and its associated priority from the queue.
-    /// This method should not be called with indices that are not present in the queue.
     /// Returns the removed priority.
     ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of range or has no associated priority.
+    ///
     /// Time complexity: `O(log n)`
     pub fn remove(&mut self, index: Index) -> Priorities::Output {
-        self.remove_index(index);
-        self.priorities.remove(index).unwrap()
+        self.try_remove(index).unwrap()
+    }
+
+    /// Removes the specified index and its associated priority from the queue.
+    /// Returns the removed priority, or an error if the index is out of range or has no associated priority.
+    ///
+    /// Time complexity: `O(log n)`
+    pub fn try_remove(&mut self, index: Index) -> Result<Priorities::Output, Error> {
+        self.check_range(index)?;
+        let priority = match self.remove_from_heap(index) {
+            Some(stored) => L::remove(&mut self.priorities, index, stored),
+            None => self.priorities.remove(index),
+        };
+        self.check_invariants();
+        priority.ok_or(Error::MissingPriority)
     }
 
     /// Removes the specified index from the queue, retaining its associated priority.
     ///
     /// Time complexity: `O(log n)`
     pub fn remove_index(&mut self, index: Index) {
-        if let Some(position) = self.positions.remove(index) {
-            self.heap.swap_remove(position);
-            if position < self.len() {
-                *self.positions.index_mut(self.heap[position]) = position;
-                self.down_heap(position);
-            }
+        if let Some(stored) = self.remove_from_heap(index) {
+            L::retain(&mut self.priorities, index, stored);
         }
+        self.check_invariants();
     }
 
     /// Clears all indices and their priorities from the queue.
@@ -138,26 +445,63 @@ where
     }
 
     /// Clears all indices from the queue.
+    ///
+    /// Time complexity: `O(n)`
     pub fn clear_indices(&mut self) {
-        self.positions.clear();
-        self.heap.clear();
+        self.positions
+            .clear_positions(&mut self.priorities, &self.heap);
+        for (index, stored) in self.heap.drain(..).zip(self.stored.drain(..)) {
+            L::retain(&mut self.priorities, index, stored);
+        }
+        self.stamps.clear();
     }
 
     /// Inserts an index-priority pair into the priority queue.
     /// Returns the previous priority associated with the index, if it existed.
     ///
+    /// # Panics
+    ///
+    /// Panics if the heap outgrows the position type of the positions map.
+    ///
     /// Time complexity: `O(log n)`
     pub fn push(&mut self, index: Index, value: Priorities::Output) -> Option<Priorities::Output> {
-        let old_priority = self.get_priority(index).cloned();
+        self.push_at(index, value).1
+    }
+
+    /// Reinserts previously removed indices into the queue with their last associated values.
+    /// Depending on the number of indices compared to the size of the queue,
+    /// the indices are either inserted one by one or the heap is rebuilt once afterwards.
+    ///
+    /// Time complexity: `O(min(k log n, n + k))`
+    pub fn extend_indices(&mut self, indices: impl IntoIterator<Item = Index>) {
+        let indices = indices.into_iter();
 
-        if self.contains(index) {
-            *self.update_dyn(index) = value;
+        if self.better_to_rebuild(indices.size_hint().0) {
+            for index in indices {
+                self.restore_index_unordered(index);
+            }
+            self.rebuild();
         } else {
-            self.priorities.insert(index, value);
-            self.restore_index(index);
+            for index in indices {
+                self.restore_index(index);
+            }
         }
+        self.check_invariants();
+    }
 
-        old_priority
+    /// Inserts an index-priority pair into the priority queue.
+    /// Returns the previous priority associated with the index, if it existed,
+    /// or an error if the index is out of range or the heap would outgrow the position type.
+    ///
+    /// Time complexity: `O(log n)`
+    pub fn try_push(
+        &mut self,
+        index: Index,
+        value: Priorities::Output,
+    ) -> Result<Option<Priorities::Output>, Error> {
+        self.check_range(index)?;
+        self.check_growth(index)?;
+        Ok(self.push(index, value))
     }
 
     /// Returns the index associated with the smallest priority in the queue, or `None` if it is empty.
@@ -171,23 +515,46 @@ where
     ///
     /// Time complexity: `O(log n)`
     pub fn pop(&mut self) -> Option<Index> {
-        if self.is_empty() {
-            return None;
-        }
+        let (popped_index, stored) = self.pop_from_heap()?;
+        L::retain(&mut self.priorities, popped_index, stored);
+        self.check_invariants();
 
-        // Removes the index associated with the smallest priority in the queue.
-        let popped_index = self.heap.swap_remove(0);
-
-        // Update positions.
-        self.positions.remove(popped_index);
+        Some(popped_index)
+    }
 
-        // Update the position for the new root and restore the heap property.
-        if let Some(new_root) = self.heap.first() {
-            *self.positions.index_mut(*new_root) = 0;
-            self.down_heap(0);
+    /// Consumes the queue and returns its indices in order of increasing priority.
+    ///
+    /// Time complexity: `O(n log n)`
+    pub fn into_sorted_vec(mut self) -> Vec<Index> {
+        let mut sorted = Vec::with_capacity(self.len());
+        while let Some(index) = self.pop() {
+            sorted.push(index);
         }
+        sorted
+    }
 
-        Some(popped_index)
+    /// Returns an iterator that removes the indices from the queue in order of increasing priority,
+    /// yielding them together with their priorities.
+    /// The priorities of drained indices are removed from the queue, while the priorities of
+    /// indices that were already removed are retained.
+    /// If the iterator is dropped early, the remaining indices and their priorities are removed as well.
+    ///
+    /// Time complexity: `O(log n)` per element
+    pub fn drain_sorted(&mut self) -> DrainSorted<'_, Index, Priorities, Positions, D, C, T, L> {
+        DrainSorted { queue: self }
+    }
+
+    /// Removes all indices from the queue in arbitrary order, retaining their associated priorities.
+    ///
+    /// Time complexity: `O(n)`
+    pub fn drain(&mut self) -> vec::Drain<'_, Index> {
+        self.positions
+            .clear_positions(&mut self.priorities, &self.heap);
+        self.stamps.clear();
+        for (&index, stored) in self.heap.iter().zip(self.stored.drain(..)) {
+            L::retain(&mut self.priorities, index, stored);
+        }
+        self.heap.drain(..)
     }
 
     /// Update the priorities of the values in the heap using a function
@@ -196,188 +563,793 @@ where
         for v in self.priorities.iter_mut() {
             f(v);
         }
+        for v in self.stored.iter_mut().filter_map(L::get_mut) {
+            f(v);
+        }
+        self.check_invariants();
     }
 
     /// Returns the index of the parent node in the heap for the given index `n`.
     fn parent(n: usize) -> usize {
-        (n - 1) / 2
+        (n - 1) / D
+    }
+
+    /// Returns the range of indices of the child nodes in the heap for the given index `n`,
+    /// or `None` if the node is a leaf.
+    fn children(&self, n: usize) -> Option<Range<usize>> {
+        let first = D * n + 1;
+        (first < self.len()).then(|| first..usize::min(first + D, self.len()))
+    }
+
+    /// Returns the index of the child node with the smallest priority for the given index `n`,
+    /// or `None` if the node is a leaf.
+    fn smallest_child(&self, n: usize) -> Option<usize> {
+        self.children(n).map(|children| {
+            children
+                .reduce(|smallest, child| match self.compare(child, smallest) {
+                    Ordering::Less => child,
+                    Ordering::Equal | Ordering::Greater => smallest,
+                })
+                .unwrap()
+        })
     }
 
-    /// Returns the index of the left child node in the heap for the given index `n`.
-    fn left(&self, n: usize) -> Option<usize> {
-        let index = 2 * n + 1;
-        (index < self.len()).then_some(index)
+    /// Returns an error if the index is out of range for the priorities or positions.
+    fn check_range(&self, index: Index) -> Result<(), Error> {
+        if self.priorities.in_range(index) && self.positions.position_in_range(index) {
+            Ok(())
+        } else {
+            Err(Error::OutOfRange)
+        }
     }
 
-    /// Returns the index of the right child node in the heap for the given index `n`.
-    fn right(&self, n: usize) -> Option<usize> {
-        let index = 2 * n + 2;
-        (index < self.len()).then_some(index)
+    /// Removes the specified index from the heap.
+    /// Returns the data stored by the layout for the index, if it was on the heap.
+    fn remove_from_heap(&mut self, index: Index) -> Option<L::Stored> {
+        let position = self
+            .positions
+            .remove_position(&mut self.priorities, index)?;
+        self.heap.swap_remove(position);
+        self.stamps.swap_remove(position);
+        let stored = self.stored.swap_remove(position);
+        if position < self.len() {
+            self.fill_hole(position);
+        }
+        Some(stored)
     }
 
-    /// Swaps the positions of two nodes in the heap by their indices.
-    fn swap(&mut self, n: usize, m: usize) {
-        *self.positions.index_mut(self.heap[n]) = m;
-        *self.positions.index_mut(self.heap[m]) = n;
-        self.heap.swap(n, m);
+    /// Removes the index associated with the smallest priority from the heap.
+    /// Returns the index and the data stored by the layout for it, or `None` if the heap is empty.
+    fn pop_from_heap(&mut self) -> Option<(Index, L::Stored)> {
+        if self.is_empty() {
+            return None;
+        }
+
+        // Removes the index associated with the smallest priority in the queue.
+        let popped_index = self.heap.swap_remove(0);
+        self.stamps.swap_remove(0);
+        let stored = self.stored.swap_remove(0);
+
+        // Update positions.
+        self.positions
+            .remove_position(&mut self.priorities, popped_index);
+
+        // Restore the heap property for the new root.
+        if !self.is_empty() {
+            self.fill_hole(0);
+        }
+
+        Some((popped_index, stored))
+    }
+
+    /// Returns `true` if the index has an associated priority, either on the heap or in the priorities map.
+    fn has_priority(&self, index: Index) -> bool {
+        self.priorities.contains(index) || L::STORES_PRIORITIES && self.contains(index)
+    }
+
+    /// Returns an error if inserting the index would grow the heap beyond the position type.
+    fn check_growth(&self, index: Index) -> Result<(), Error> {
+        if self.contains(index) || self.positions.position_fits(self.len()) {
+            Ok(())
+        } else {
+            Err(Error::PositionOverflow)
+        }
+    }
+
+    /// Reinserts a previously removed index into the queue with its last associated value.
+    /// Returns the heap index of the index.
+    fn restore_index_at(&mut self, index: Index) -> usize {
+        let position = self.len();
+        let position = match self
+            .positions
+            .insert_position(&mut self.priorities, index, position)
+        {
+            Some(current) => current,
+            None => {
+                self.heap.push(index);
+                self.stamps.push(self.tie_break.stamp());
+                self.stored.push(L::restore(&mut self.priorities, index));
+                self.up_heap(position)
+            }
+        };
+        self.check_invariants();
+        position
+    }
+
+    /// Inserts an index-priority pair into the priority queue.
+    /// Returns the heap index of the index and the previous priority associated with it, if it existed.
+    fn push_at(
+        &mut self,
+        index: Index,
+        value: Priorities::Output,
+    ) -> (usize, Option<Priorities::Output>) {
+        let position = self.len();
+        let (current, old_priority) = L::push_priority(
+            &mut self.positions,
+            &mut self.priorities,
+            &mut self.stored,
+            index,
+            value,
+            position,
+        );
+        let position = match current {
+            Some(current) => self.sift(current),
+            None => {
+                self.heap.push(index);
+                self.stamps.push(self.tie_break.stamp());
+                self.up_heap(position)
+            }
+        };
+        self.check_invariants();
+        (position, old_priority)
+    }
+
+    /// Inserts an index-priority pair at the end of the heap without restoring the heap property.
+    fn push_unordered(&mut self, index: Index, value: Priorities::Output) {
+        let position = self.len();
+        let (current, _) = L::push_priority(
+            &mut self.positions,
+            &mut self.priorities,
+            &mut self.stored,
+            index,
+            value,
+            position,
+        );
+        if current.is_none() {
+            self.heap.push(index);
+            self.stamps.push(self.tie_break.stamp());
+        }
+    }
+
+    /// Reinserts a previously removed index at the end of the heap without restoring the heap property.
+    fn restore_index_unordered(&mut self, index: Index) {
+        let position = self.len();
+        if self
+            .positions
+            .insert_position(&mut self.priorities, index, position)
+            .is_none()
+        {
+            self.heap.push(index);
+            self.stamps.push(self.tie_break.stamp());
+            self.stored.push(L::restore(&mut self.priorities, index));
+        }
+    }
+
+    /// Returns `true` if inserting `additional` indices is expected to be cheaper
+    /// by rebuilding the heap than by bubbling up each index individually.
+    fn better_to_rebuild(&self, additional: usize) -> bool {
+        if self.len() <= 1 {
+            return additional > 1;
+        }
+        2 * (self.len() + additional) < additional * self.len().ilog2() as usize
+    }
+
+    /// Restores the heap property for the entire heap using Floyd's bottom-up construction.
+    ///
+    /// Time complexity: `O(n)`
+    fn rebuild(&mut self) {
+        if self.len() > 1 {
+            for n in (0..=Self::parent(self.len() - 1)).rev() {
+                self.down_heap(n);
+            }
+        }
+    }
+
+    /// Moves the node at heap index `from` into the hole at heap index `to`, writing its position.
+    /// The data stored by the layout is swapped instead, so that it moves along with the hole.
+    fn move_into_hole(&mut self, from: usize, to: usize) {
+        self.heap[to] = self.heap[from];
+        self.stamps[to] = self.stamps[from];
+        self.stored.swap(from, to);
+        self.positions
+            .set_position(&mut self.priorities, self.heap[to], to);
+    }
+
+    /// Places the given node into the hole at heap index `n`, writing its position.
+    fn place(&mut self, n: usize, index: Index, stamp: T::Stamp) {
+        self.heap[n] = index;
+        self.stamps[n] = stamp;
+        self.positions.set_position(&mut self.priorities, index, n);
+    }
+
+    /// Returns the heap index of the given index, or `None` if it is not in the queue.
+    fn position(&self, index: Index) -> Option<usize> {
+        self.positions.position(&self.priorities, index)
+    }
+
+    /// Returns the priority of the node at the given heap index.
+    fn priority_at(&self, n: usize) -> &Priorities::Output {
+        L::get(&self.stored[n]).unwrap_or_else(|| self.priorities.index(self.heap[n]))
+    }
+
+    /// Returns a mutable reference to the priority of the node at the given heap index.
+    fn priority_at_mut(&mut self, n: usize) -> &mut Priorities::Output {
+        match L::get_mut(&mut self.stored[n]) {
+            Some(priority) => priority,
+            None => self.priorities.index_mut(self.heap[n]),
+        }
     }
 
     /// Compares the priorities of two nodes in the heap.
     fn compare(&self, n: usize, m: usize) -> Ordering {
-        self.priorities
-            .index(self.heap[n])
-            .cmp(self.priorities.index(self.heap[m]))
+        self.compare
+            .compare(self.priority_at(n), self.priority_at(m))
+            .then_with(|| self.tie_break.compare(&self.stamps[n], &self.stamps[m]))
     }
 
-    /// Performs up-heap bubbling from the given heap index.
-    fn up_heap(&mut self, mut n: usize) {
-        while n > 0 {
-            let parent = Self::parent(n);
+    /// Compares the priority of the node at heap index `m` to the priority of the given node,
+    /// which is lifted out of the heap and whose hole is at heap index `hole`.
+    fn compare_to_hole(&self, m: usize, hole: usize, index: Index, stamp: &T::Stamp) -> Ordering {
+        let priority = L::get(&self.stored[hole]).unwrap_or_else(|| self.priorities.index(index));
+        self.compare
+            .compare(self.priority_at(m), priority)
+            .then_with(|| self.tie_break.compare(&self.stamps[m], stamp))
+    }
 
-            if self.compare(parent, n).is_gt() {
-                self.swap(parent, n);
-                n = parent;
+    /// Moves the hole of the given node up from heap index `hole`, while its parents are larger.
+    /// Returns the final heap index of the hole.
+    fn hole_up(&mut self, mut hole: usize, index: Index, stamp: &T::Stamp) -> usize {
+        while hole > 0 {
+            let parent = Self::parent(hole);
+
+            if self.compare_to_hole(parent, hole, index, stamp).is_gt() {
+                self.move_into_hole(parent, hole);
+                hole = parent;
             } else {
                 break;
             }
         }
+        hole
+    }
+
+    /// Performs up-heap bubbling from the given heap index.
+    /// Rather than swapping at every level, larger parents are moved down into a hole,
+    /// so every moved node has its position written exactly once.
+    /// Returns the final heap index of the bubbled node.
+    fn up_heap(&mut self, n: usize) -> usize {
+        let (index, stamp) = (self.heap[n], self.stamps[n]);
+        let hole = self.hole_up(n, index, &stamp);
+        if hole != n {
+            self.place(hole, index, stamp);
+        }
+        hole
+    }
+
+    /// Restores the heap property after the node at the given heap index was replaced by the last node,
+    /// using Floyd's bottom-up strategy: the hole is first moved down to a leaf along the smallest children,
+    /// without comparing them to the replacing node, and the replacing node is then bubbled up from there.
+    /// Since the last node usually belongs near the bottom, this saves about half of the comparisons.
+    fn fill_hole(&mut self, n: usize) {
+        let (index, stamp) = (self.heap[n], self.stamps[n]);
+        let mut hole = n;
+        while let Some(smallest_child_index) = self.smallest_child(hole) {
+            self.move_into_hole(smallest_child_index, hole);
+            hole = smallest_child_index;
+        }
+        let hole = self.hole_up(hole, index, &stamp);
+        self.place(hole, index, stamp);
+    }
+
+    /// Performs up-heap or down-heap bubbling from the given heap index, whichever is required.
+    /// Returns the final heap index of the bubbled node.
+    fn sift(&mut self, n: usize) -> usize {
+        if n > 0 && self.compare(Self::parent(n), n).is_gt() {
+            self.up_heap(n)
+        } else {
+            self.down_heap(n)
+        }
     }
 
     /// Performs down-heap bubbling from the given heap index.
-    fn down_heap(&mut self, mut n: usize) {
-        while let Some(left_index) = self.left(n) {
-            let smallest_child_index = match self.right(n) {
-                None => left_index,
-                Some(right_index) => match self.compare(left_index, right_index) {
-                    Ordering::Less | Ordering::Equal => left_index,
-                    Ordering::Greater => right_index,
-                },
-            };
-
-            if self.compare(smallest_child_index, n).is_lt() {
-                self.swap(smallest_child_index, n);
-                n = smallest_child_index;
+    /// Like `up_heap`, smaller children are moved up into a hole.
+    /// Returns the final heap index of the bubbled node.
+    fn down_heap(&mut self, n: usize) -> usize {
+        let (index, stamp) = (self.heap[n], self.stamps[n]);
+        let mut hole = n;
+        while let Some(smallest_child_index) = self.smallest_child(hole) {
+            if self
+                .compare_to_hole(smallest_child_index, hole, index, &stamp)
+                .is_lt()
+            {
+                self.move_into_hole(smallest_child_index, hole);
+                hole = smallest_child_index;
             } else {
                 break;
             }
         }
+        if hole != n {
+            self.place(hole, index, stamp);
+        }
+        hole
     }
 }
 
-impl<Index, Priorities, Positions> Default for IndexedPriorityQueue<Index, Priorities, Positions>
+impl<Index, Priorities, Positions, const D: usize, C, T, L> Default
+    for IndexedPriorityQueue<Index, Priorities, Positions, D, C, T, L>
 where
     Index: Copy,
-    Priorities: Indexed<Index = Index, Output: Ord + Clone> + Default,
-    Positions: Indexed<Index = Index, Output = usize> + Default,
+    Priorities: Indexed<Index = Index> + Default,
+    C: Compare<Priorities::Output> + Default,
+    T: TieBreak + Default,
+    L: Layout<Priorities::Output>,
+    Positions: PositionMap<Priorities> + Default,
 {
     fn default() -> Self {
         Self::new(Priorities::default(), Positions::default())
     }
 }
 
+/// Iterator that drains an `IndexedPriorityQueue` in order of increasing priority.
+/// Created by [`IndexedPriorityQueue::drain_sorted`].
+pub struct DrainSorted<
+    'a,
+    Index,
+    Priorities,
+    Positions,
+    const D: usize = 2,
+    C = MinOrder,
+    T = NoTieBreak,
+    L = Indirect,
+> where
+    Index: Copy,
+    Priorities: Indexed<Index = Index>,
+    C: Compare<Priorities::Output>,
+    T: TieBreak,
+    L: Layout<Priorities::Output>,
+    Positions: PositionMap<Priorities>,
+{
+    queue: &'a mut IndexedPriorityQueue<Index, Priorities, Positions, D, C, T, L>,
+}
+
+impl<'a, Index, Priorities, Positions, const D: usize, C, T, L> Iterator
+    for DrainSorted<'a, Index, Priorities, Positions, D, C, T, L>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index>,
+    C: Compare<Priorities::Output>,
+    T: TieBreak,
+    L: Layout<Priorities::Output>,
+    Positions: PositionMap<Priorities>,
+{
+    type Item = (Index, Priorities::Output);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (index, stored) = self.queue.pop_from_heap()?;
+        let priority = L::remove(&mut self.queue.priorities, index, stored).unwrap();
+        self.queue.check_invariants();
+        Some((index, priority))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.queue.len(), Some(self.queue.len()))
+    }
+}
+
+impl<'a, Index, Priorities, Positions, const D: usize, C, T, L> ExactSizeIterator
+    for DrainSorted<'a, Index, Priorities, Positions, D, C, T, L>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index>,
+    C: Compare<Priorities::Output>,
+    T: TieBreak,
+    L: Layout<Priorities::Output>,
+    Positions: PositionMap<Priorities>,
+{
+}
+
+impl<'a, Index, Priorities, Positions, const D: usize, C, T, L> Drop
+    for DrainSorted<'a, Index, Priorities, Positions, D, C, T, L>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index>,
+    C: Compare<Priorities::Output>,
+    T: TieBreak,
+    L: Layout<Priorities::Output>,
+    Positions: PositionMap<Priorities>,
+{
+    fn drop(&mut self) {
+        let queue = &mut *self.queue;
+
+        // Joint maps store the positions along with the priorities, so the positions are cleared
+        // before the priorities are removed.
+        queue
+            .positions
+            .clear_positions(&mut queue.priorities, &queue.heap);
+        for (index, stored) in queue.heap.drain(..).zip(queue.stored.drain(..)) {
+            L::remove(&mut queue.priorities, index, stored);
+        }
+        queue.stamps.clear();
+    }
+}
+
+/// Iterator over the indices in an `IndexedPriorityQueue` and their priorities.
+/// Created by [`IndexedPriorityQueue::iter`].
+pub struct Iter<'a, Index, Priorities, L = Indirect>
+where
+    Priorities: Indexed<Index = Index>,
+    L: Layout<Priorities::Output>,
+{
+    indices: slice::Iter<'a, Index>,
+    stored: slice::Iter<'a, L::Stored>,
+    priorities: &'a Priorities,
+}
+
+impl<'a, Index, Priorities, L> Iterator for Iter<'a, Index, Priorities, L>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index>,
+    L: Layout<Priorities::Output>,
+{
+    type Item = (Index, &'a Priorities::Output);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = *self.indices.next()?;
+        let stored = self.stored.next().unwrap();
+        Some((
+            index,
+            L::get(stored).unwrap_or_else(|| self.priorities.index(index)),
+        ))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indices.size_hint()
+    }
+}
+
+impl<'a, Index, Priorities, L> ExactSizeIterator for Iter<'a, Index, Priorities, L>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index>,
+    L: Layout<Priorities::Output>,
+{
+}
+
+/// Owning iterator over the indices in an `IndexedPriorityQueue` and their priorities, in arbitrary order.
+/// Priorities of indices that are not in the queue are dropped.
+pub struct IntoIter<Index, Priorities, L = Indirect>
+where
+    Priorities: Indexed<Index = Index>,
+    L: Layout<Priorities::Output>,
+{
+    indices: vec::IntoIter<Index>,
+    stored: vec::IntoIter<L::Stored>,
+    priorities: Priorities,
+}
+
+impl<Index, Priorities, L> Iterator for IntoIter<Index, Priorities, L>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index>,
+    L: Layout<Priorities::Output>,
+{
+    type Item = (Index, Priorities::Output);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.indices.next()?;
+        let stored = self.stored.next().unwrap();
+        Some((
+            index,
+            L::remove(&mut self.priorities, index, stored).unwrap(),
+        ))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indices.size_hint()
+    }
+}
+
+impl<Index, Priorities, L> ExactSizeIterator for IntoIter<Index, Priorities, L>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index>,
+    L: Layout<Priorities::Output>,
+{
+}
+
+impl<'a, Index, Priorities, Positions, const D: usize, C, T, L> IntoIterator
+    for &'a IndexedPriorityQueue<Index, Priorities, Positions, D, C, T, L>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index>,
+    C: Compare<Priorities::Output>,
+    T: TieBreak,
+    L: Layout<Priorities::Output>,
+    Positions: PositionMap<Priorities>,
+{
+    type Item = (Index, &'a Priorities::Output);
+    type IntoIter = Iter<'a, Index, Priorities, L>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<Index, Priorities, Positions, const D: usize, C, T, L> IntoIterator
+    for IndexedPriorityQueue<Index, Priorities, Positions, D, C, T, L>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index>,
+    C: Compare<Priorities::Output>,
+    T: TieBreak,
+    L: Layout<Priorities::Output>,
+    Positions: PositionMap<Priorities>,
+{
+    type Item = (Index, Priorities::Output);
+    type IntoIter = IntoIter<Index, Priorities, L>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            indices: self.heap.into_iter(),
+            stored: self.stored.into_iter(),
+            priorities: self.priorities,
+        }
+    }
+}
+
+impl<Index, Priorities, Positions, const D: usize, C, T, L>
+    FromIterator<(Index, Priorities::Output)>
+    for IndexedPriorityQueue<Index, Priorities, Positions, D, C, T, L>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index> + Default,
+    C: Compare<Priorities::Output> + Default,
+    T: TieBreak + Default,
+    L: Layout<Priorities::Output>,
+    Positions: PositionMap<Priorities> + Default,
+{
+    fn from_iter<I: IntoIterator<Item = (Index, Priorities::Output)>>(iter: I) -> Self {
+        Self::heapify(Priorities::default(), Positions::default(), iter)
+    }
+}
+
+impl<Index, Priorities, Positions, const D: usize, C, T, L> Extend<(Index, Priorities::Output)>
+    for IndexedPriorityQueue<Index, Priorities, Positions, D, C, T, L>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index>,
+    C: Compare<Priorities::Output>,
+    T: TieBreak,
+    L: Layout<Priorities::Output>,
+    Positions: PositionMap<Priorities>,
+{
+    fn extend<I: IntoIterator<Item = (Index, Priorities::Output)>>(&mut self, iter: I) {
+        let pairs = iter.into_iter();
+
+        if self.better_to_rebuild(pairs.size_hint().0) {
+            for (index, priority) in pairs {
+                self.push_unordered(index, priority);
+            }
+            self.rebuild();
+        } else {
+            for (index, priority) in pairs {
+                self.push(index, priority);
+            }
+        }
+        self.check_invariants();
+    }
+}
+
+impl<Index, Priorities, Positions, const D: usize, C, T, L> From<Vec<(Index, Priorities::Output)>>
+    for IndexedPriorityQueue<Index, Priorities, Positions, D, C, T, L>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index> + Default,
+    C: Compare<Priorities::Output> + Default,
+    T: TieBreak + Default,
+    L: Layout<Priorities::Output>,
+    Positions: PositionMap<Priorities> + Default,
+{
+    fn from(pairs: Vec<(Index, Priorities::Output)>) -> Self {
+        Self::from_iter(pairs)
+    }
+}
+
 macro_rules! generate_get_mut {
-    ($struct_name:ident, $function_name:ident $(, $cfg_condition:tt)*) => {
-        impl<Index, Priorities, Positions> IndexedPriorityQueue<Index, Priorities, Positions>
+    ($struct_name:ident, $function_name:ident, $try_function_name:ident) => {
+        impl<Index, Priorities, Positions, const D: usize, C, T, L>
+            IndexedPriorityQueue<Index, Priorities, Positions, D, C, T, L>
         where
             Index: Copy,
-            Priorities: Indexed<Index=Index, Output: Ord + Clone>,
-            Positions: Indexed<Index=Index, Output = usize>,
+            Priorities: Indexed<Index = Index>,
+            C: Compare<Priorities::Output>,
+            T: TieBreak,
+            L: Layout<Priorities::Output>,
+            Positions: PositionMap<Priorities>,
         {
-            pub fn $function_name(&mut self, index: Index) -> $struct_name<Index, Priorities, Positions> {
-                $struct_name {
-                    $(#[cfg($cfg_condition)])*
-                    old_value: self.priorities.index(index).clone(),
-                    heap: self,
-                    index
+            #[doc = concat!("Panicking version of [`Self::", stringify!($try_function_name), "`].")]
+            ///
+            /// # Panics
+            ///
+            /// Panics if the index is out of range or has no associated priority.
+            pub fn $function_name(
+                &mut self,
+                index: Index,
+            ) -> $struct_name<'_, Index, Priorities, Positions, D, C, T, L> {
+                self.$try_function_name(index).unwrap()
+            }
+
+            /// Returns a guard to the priority associated with the specified index, which restores the heap
+            /// property when dropped, or an error if the index is out of range or has no associated priority.
+            pub fn $try_function_name(
+                &mut self,
+                index: Index,
+            ) -> Result<$struct_name<'_, Index, Priorities, Positions, D, C, T, L>, Error> {
+                self.check_range(index)?;
+                if !self.has_priority(index) {
+                    return Err(Error::MissingPriority);
                 }
+
+                let position = self.position(index);
+                Ok($struct_name {
+                    heap: self,
+                    index,
+                    position,
+                })
             }
         }
 
-        pub struct $struct_name<'a, Index, Priorities, Positions>
-        where
+        pub struct $struct_name<
+            'a,
+            Index,
+            Priorities,
+            Positions,
+            const D: usize = 2,
+            C = MinOrder,
+            T = NoTieBreak,
+            L = Indirect,
+        > where
             Index: Copy,
-            Priorities: Indexed<Index=Index, Output: Ord + Clone>,
-            Positions: Indexed<Index=Index, Output = usize>,
+            Priorities: Indexed<Index = Index>,
+            C: Compare<Priorities::Output>,
+            T: TieBreak,
+            L: Layout<Priorities::Output>,
+            Positions: PositionMap<Priorities>,
         {
-            $(#[cfg($cfg_condition)])*
-            old_value: Priorities::Output,
-            heap: &'a mut IndexedPriorityQueue<Index, Priorities, Positions>,
+            heap: &'a mut IndexedPriorityQueue<Index, Priorities, Positions, D, C, T, L>,
             index: Index,
+            /// The heap index of the index, which stays fixed while the guard is alive.
+            position: Option<usize>,
         }
 
-        impl<'a, Index, Priorities, Positions> Deref for $struct_name<'a, Index, Priorities, Positions>
+        impl<'a, Index, Priorities, Positions, const D: usize, C, T, L> Deref
+            for $struct_name<'a, Index, Priorities, Positions, D, C, T, L>
         where
             Index: Copy,
-            Priorities: Indexed<Index=Index, Output: Ord + Clone>,
-            Positions: Indexed<Index=Index, Output = usize>,
+            Priorities: Indexed<Index = Index>,
+            C: Compare<Priorities::Output>,
+            T: TieBreak,
+            L: Layout<Priorities::Output>,
+            Positions: PositionMap<Priorities>,
         {
             type Target = Priorities::Output;
 
             fn deref(&self) -> &Self::Target {
-                self.heap.priorities.index(self.index)
+                match self.position {
+                    Some(position) => self.heap.priority_at(position),
+                    None => self.heap.priorities.index(self.index),
+                }
             }
         }
 
-        impl<'a, Index, Priorities, Positions> DerefMut for $struct_name<'a, Index, Priorities, Positions>
+        impl<'a, Index, Priorities, Positions, const D: usize, C, T, L> DerefMut
+            for $struct_name<'a, Index, Priorities, Positions, D, C, T, L>
         where
             Index: Copy,
-            Priorities: Indexed<Index=Index, Output: Ord + Clone>,
-            Positions: Indexed<Index=Index, Output = usize>,
+            Priorities: Indexed<Index = Index>,
+            C: Compare<Priorities::Output>,
+            T: TieBreak,
+            L: Layout<Priorities::Output>,
+            Positions: PositionMap<Priorities>,
         {
             fn deref_mut(&mut self) -> &mut Self::Target {
-                self.heap.priorities.index_mut(self.index)
+                match self.position {
+                    Some(position) => self.heap.priority_at_mut(position),
+                    None => self.heap.priorities.index_mut(self.index),
+                }
             }
         }
     };
 }
 
-generate_get_mut!(IPQMutRefUp, update_up, debug_assertions);
-generate_get_mut!(IPQMutRefDown, update_down, debug_assertions);
-generate_get_mut!(IPQMutRefDyn, update_dyn);
+generate_get_mut!(IPQMutRefUp, update_up, try_update_up);
+generate_get_mut!(IPQMutRefDown, update_down, try_update_down);
+generate_get_mut!(IPQMutRefDyn, update_dyn, try_update_dyn);
 
-impl<'a, Index, Priorities, Positions> Drop for IPQMutRefUp<'a, Index, Priorities, Positions>
+impl<'a, Index, Priorities, Positions, const D: usize, C, T, L> Drop
+    for IPQMutRefUp<'a, Index, Priorities, Positions, D, C, T, L>
 where
     Index: Copy,
-    Priorities: Indexed<Index = Index, Output: Ord + Clone>,
-    Positions: Indexed<Index = Index, Output = usize>,
+    Priorities: Indexed<Index = Index>,
+    C: Compare<Priorities::Output>,
+    T: TieBreak,
+    L: Layout<Priorities::Output>,
+    Positions: PositionMap<Priorities>,
 {
     fn drop(&mut self) {
-        #[cfg(debug_assertions)]
-        assert!(*self.heap.priorities.index(self.index) >= self.old_value);
-        if let Some(position) = self.heap.positions.get(self.index) {
-            self.heap.down_heap(*position);
+        if let Some(position) = self.position {
+            let _position = self.heap.down_heap(position);
+
+            // If the priority was decreased instead, it may now be smaller than its parent.
+            #[cfg(any(debug_assertions, feature = "checked"))]
+            assert!(
+                _position == 0
+                    || self
+                        .heap
+                        .compare(
+                            IndexedPriorityQueue::<Index, Priorities, Positions, D, C, T, L>::parent(
+                                _position
+                            ),
+                            _position
+                        )
+                        .is_le(),
+                "`update_up` must not decrease the priority"
+            );
         }
+        self.heap.check_invariants();
     }
 }
 
-impl<'a, Index, Priorities, Positions> Drop for IPQMutRefDown<'a, Index, Priorities, Positions>
+impl<'a, Index, Priorities, Positions, const D: usize, C, T, L> Drop
+    for IPQMutRefDown<'a, Index, Priorities, Positions, D, C, T, L>
 where
     Index: Copy,
-    Priorities: Indexed<Index = Index, Output: Ord + Clone>,
-    Positions: Indexed<Index = Index, Output = usize>,
+    Priorities: Indexed<Index = Index>,
+    C: Compare<Priorities::Output>,
+    T: TieBreak,
+    L: Layout<Priorities::Output>,
+    Positions: PositionMap<Priorities>,
 {
     fn drop(&mut self) {
-        #[cfg(debug_assertions)]
-        assert!(*self.heap.priorities.index(self.index) <= self.old_value);
-        if let Some(position) = self.heap.positions.get(self.index) {
-            self.heap.up_heap(*position);
+        if let Some(position) = self.position {
+            let _position = self.heap.up_heap(position);
+
+            // If the priority was increased instead, it may now be larger than one of its children.
+            #[cfg(any(debug_assertions, feature = "checked"))]
+            assert!(
+                self.heap
+                    .smallest_child(_position)
+                    .is_none_or(|child| self.heap.compare(_position, child).is_le()),
+                "`update_down` must not increase the priority"
+            );
         }
+        self.heap.check_invariants();
     }
 }
 
-impl<'a, Index, Priorities, Positions> Drop for IPQMutRefDyn<'a, Index, Priorities, Positions>
+impl<'a, Index, Priorities, Positions, const D: usize, C, T, L> Drop
+    for IPQMutRefDyn<'a, Index, Priorities, Positions, D, C, T, L>
 where
     Index: Copy,
-    Priorities: Indexed<Index = Index, Output: Ord + Clone>,
-    Positions: Indexed<Index = Index, Output = usize>,
+    Priorities: Indexed<Index = Index>,
+    C: Compare<Priorities::Output>,
+    T: TieBreak,
+    L: Layout<Priorities::Output>,
+    Positions: PositionMap<Priorities>,
 {
     fn drop(&mut self) {
-        if let Some(position) = self.heap.positions.get(self.index) {
-            match self.heap.priorities.index(self.index).cmp(&self.old_value) {
-                Ordering::Greater => self.heap.down_heap(*position),
-                Ordering::Less => self.heap.up_heap(*position),
-                Ordering::Equal => {}
-            }
+        if let Some(position) = self.position {
+            self.heap.sift(position);
         }
+        self.heap.check_invariants();
     }
 }
@@ -0,0 +1,413 @@
+use crate::compare::{Compare, MinOrder};
+use crate::indexed::Indexed;
+use crate::Error;
+use std::mem;
+use std::ops::{Deref, DerefMut};
+
+/// Indexed double-ended priority queue backed by a min-max heap.
+///
+/// Nodes on even levels (starting with the root) are smaller than all of their descendants,
+/// nodes on odd levels are larger than all of their descendants. The smallest priority is thus
+/// found at the root and the largest one at one of its children.
+#[derive(Debug)]
+pub struct IndexedMinMaxQueue<Index, Priorities, Positions, C = MinOrder>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index>,
+    C: Compare<Priorities::Output>,
+    Positions: Indexed<Index = Index, Output = usize>,
+{
+    /// The priorities associated with indexes on the heap.
+    priorities: Priorities,
+    /// The positions of the indexes on the heap.
+    positions: Positions,
+    /// The heap storing the indexes.
+    heap: Vec<Index>,
+    /// The comparator defining the order of the priorities.
+    compare: C,
+}
+
+impl<Index, Priorities, Positions, C> IndexedMinMaxQueue<Index, Priorities, Positions, C>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index>,
+    C: Compare<Priorities::Output>,
+    Positions: Indexed<Index = Index, Output = usize>,
+{
+    /// Constructs a new, empty `IndexedMinMaxQueue`.
+    pub fn new(priorities: impl Into<Priorities>, positions: impl Into<Positions>) -> Self
+    where
+        C: Default,
+    {
+        Self::with_comparator(priorities, positions, C::default())
+    }
+
+    /// Constructs a new, empty `IndexedMinMaxQueue` that orders priorities using the given comparator.
+    pub fn with_comparator(
+        priorities: impl Into<Priorities>,
+        positions: impl Into<Positions>,
+        compare: C,
+    ) -> Self {
+        Self {
+            priorities: priorities.into(),
+            positions: positions.into(),
+            heap: Vec::new(),
+            compare,
+        }
+    }
+
+    /// Returns the number of indices in the queue.
+    ///
+    /// Time complexity: `O(1)`
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// Returns `true` if the queue contains no indices.
+    ///
+    /// Time complexity: `O(1)`
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Returns `true` if the queue contains the specified index.
+    ///
+    /// Time complexity: `O(1)`
+    pub fn contains(&self, index: Index) -> bool {
+        self.positions.contains(index)
+    }
+
+    /// Returns the priority associated with the specified index, or `None`, if the index has no priority.
+    ///
+    /// Time complexity: `O(1)`
+    pub fn get_priority(&self, index: Index) -> Option<&Priorities::Output> {
+        self.priorities.get(index)
+    }
+
+    /// Returns the index associated with the smallest priority in the queue, or `None` if it is empty.
+    ///
+    /// Time complexity: `O(1)`
+    pub fn min(&self) -> Option<&Index> {
+        self.heap.first()
+    }
+
+    /// Returns the smallest priority in the queue, or `None`, if it is empty.
+    ///
+    /// Time complexity: `O(1)`
+    pub fn min_priority(&self) -> Option<&Priorities::Output> {
+        self.min().map(|index| self.priorities.index(*index))
+    }
+
+    /// Returns the index associated with the largest priority in the queue, or `None` if it is empty.
+    ///
+    /// Time complexity: `O(1)`
+    pub fn max(&self) -> Option<&Index> {
+        self.max_position().map(|position| &self.heap[position])
+    }
+
+    /// Returns the largest priority in the queue, or `None`, if it is empty.
+    ///
+    /// Time complexity: `O(1)`
+    pub fn max_priority(&self) -> Option<&Priorities::Output> {
+        self.max().map(|index| self.priorities.index(*index))
+    }
+
+    /// Inserts an index-priority pair into the queue.
+    /// Returns the previous priority associated with the index, if it existed.
+    ///
+    /// Time complexity: `O(log n)`
+    pub fn push(&mut self, index: Index, value: Priorities::Output) -> Option<Priorities::Output> {
+        match self.positions.get(index) {
+            Some(&position) => {
+                let old_priority = mem::replace(self.priorities.index_mut(index), value);
+                self.sift(position);
+                Some(old_priority)
+            }
+            None => {
+                let old_priority = self.priorities.insert(index, value);
+                self.restore_index(index);
+                old_priority
+            }
+        }
+    }
+
+    /// Reinserts a previously removed index into the queue with its last associated value.
+    ///
+    /// Time complexity: `O(log n)`
+    pub fn restore_index(&mut self, index: Index) {
+        if !self.positions.contains(index) {
+            let position = self.heap.len();
+            self.heap.push(index);
+            self.positions.insert(index, position);
+            self.sift(position);
+        }
+    }
+
+    /// Removes and returns the index associated with the smallest priority in the queue, or `None` if it is empty.
+    ///
+    /// Time complexity: `O(log n)`
+    pub fn pop_min(&mut self) -> Option<Index> {
+        let index = *self.min()?;
+        self.remove_at(0);
+        Some(index)
+    }
+
+    /// Removes and returns the index associated with the largest priority in the queue, or `None` if it is empty.
+    ///
+    /// Time complexity: `O(log n)`
+    pub fn pop_max(&mut self) -> Option<Index> {
+        let position = self.max_position()?;
+        let index = self.heap[position];
+        self.remove_at(position);
+        Some(index)
+    }
+
+    /// Removes the specified index and its associated priority from the queue.
+    /// Returns the removed priority.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index has no associated priority.
+    ///
+    /// Time complexity: `O(log n)`
+    pub fn remove(&mut self, index: Index) -> Priorities::Output {
+        self.remove_index(index);
+        self.priorities.remove(index).unwrap()
+    }
+
+    /// Removes the specified index from the queue, retaining its associated priority.
+    ///
+    /// Time complexity: `O(log n)`
+    pub fn remove_index(&mut self, index: Index) {
+        if let Some(&position) = self.positions.get(index) {
+            self.remove_at(position);
+        }
+    }
+
+    /// Clears all indices and their priorities from the queue.
+    pub fn clear(&mut self) {
+        self.clear_indices();
+        self.priorities.clear();
+    }
+
+    /// Clears all indices from the queue.
+    ///
+    /// Time complexity: `O(n)`
+    pub fn clear_indices(&mut self) {
+        for &index in &self.heap {
+            self.positions.remove(index);
+        }
+        self.heap.clear();
+    }
+
+    /// Modifies the priority associated with the specified index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index has no associated priority.
+    ///
+    /// Time complexity: `O(log n)`
+    pub fn update_dyn(
+        &mut self,
+        index: Index,
+    ) -> MinMaxMutRef<'_, Index, Priorities, Positions, C> {
+        self.try_update_dyn(index).unwrap()
+    }
+
+    /// Modifies the priority associated with the specified index.
+    /// Returns an error if the index has no associated priority.
+    ///
+    /// Time complexity: `O(log n)`
+    pub fn try_update_dyn(
+        &mut self,
+        index: Index,
+    ) -> Result<MinMaxMutRef<'_, Index, Priorities, Positions, C>, Error> {
+        if !self.priorities.contains(index) {
+            return Err(Error::MissingPriority);
+        }
+
+        Ok(MinMaxMutRef { queue: self, index })
+    }
+
+    /// Returns the position of the largest priority, which is the root or one of its children.
+    fn max_position(&self) -> Option<usize> {
+        match self.heap.len() {
+            0 => None,
+            1 => Some(0),
+            2 => Some(1),
+            _ => Some(if self.less(1, 2) { 2 } else { 1 }),
+        }
+    }
+
+    /// Returns `true` if the position is on a level whose nodes are smaller than their descendants.
+    fn is_min_level(position: usize) -> bool {
+        (position + 1).ilog2().is_multiple_of(2)
+    }
+
+    /// Compares the priorities at two positions.
+    fn less(&self, a: usize, b: usize) -> bool {
+        self.compare
+            .compare(
+                self.priorities.index(self.heap[a]),
+                self.priorities.index(self.heap[b]),
+            )
+            .is_lt()
+    }
+
+    /// Compares the priorities at two positions, in reverse order on max levels.
+    fn before(&self, a: usize, b: usize, min_level: bool) -> bool {
+        if min_level {
+            self.less(a, b)
+        } else {
+            self.less(b, a)
+        }
+    }
+
+    /// Swaps two positions of the heap.
+    fn swap(&mut self, a: usize, b: usize) {
+        self.heap.swap(a, b);
+        *self.positions.index_mut(self.heap[a]) = a;
+        *self.positions.index_mut(self.heap[b]) = b;
+    }
+
+    /// Removes the index at the given position from the heap.
+    fn remove_at(&mut self, position: usize) {
+        let last = self.heap.len() - 1;
+        self.swap(position, last);
+        let index = self.heap.pop().unwrap();
+        self.positions.remove(index);
+        if position < last {
+            self.sift(position);
+        }
+    }
+
+    /// Restores the heap property for a position whose priority has changed arbitrarily.
+    fn sift(&mut self, position: usize) {
+        let min_level = Self::is_min_level(position);
+        if position > 0 && self.before((position - 1) / 2, position, min_level) {
+            // The node belongs to the levels of its parent, whose priority in turn has to be
+            // moved down from the current position.
+            let parent = (position - 1) / 2;
+            self.swap(parent, position);
+            self.push_up(parent, !min_level);
+            self.push_down(position);
+        } else if self.push_up(position, min_level) == position {
+            self.push_down(position);
+        }
+    }
+
+    /// Moves the node at the given position up along the ancestors on the same kind of level.
+    /// Returns the final position of the node.
+    fn push_up(&mut self, mut position: usize, min_level: bool) -> usize {
+        while position > 2 {
+            let grandparent = (position - 3) / 4;
+            if !self.before(position, grandparent, min_level) {
+                break;
+            }
+            self.swap(position, grandparent);
+            position = grandparent;
+        }
+        position
+    }
+
+    /// Moves the node at the given position down until it is consistent with its descendants.
+    fn push_down(&mut self, mut position: usize) {
+        let min_level = Self::is_min_level(position);
+        loop {
+            let first_child = 2 * position + 1;
+            if first_child >= self.heap.len() {
+                return;
+            }
+
+            // The first among the children and grandchildren.
+            let first_grandchild = 4 * position + 3;
+            let candidate = (first_child..first_child + 2)
+                .chain(first_grandchild..first_grandchild + 4)
+                .filter(|&node| node < self.heap.len())
+                .reduce(|a, b| if self.before(b, a, min_level) { b } else { a })
+                .unwrap();
+
+            if !self.before(candidate, position, min_level) {
+                return;
+            }
+            self.swap(candidate, position);
+            if candidate < first_grandchild {
+                return;
+            }
+
+            let parent = (candidate - 1) / 2;
+            if self.before(parent, candidate, min_level) {
+                self.swap(parent, candidate);
+            }
+            position = candidate;
+        }
+    }
+}
+
+impl<Index, Priorities, Positions, C> Default
+    for IndexedMinMaxQueue<Index, Priorities, Positions, C>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index> + Default,
+    C: Compare<Priorities::Output> + Default,
+    Positions: Indexed<Index = Index, Output = usize> + Default,
+{
+    fn default() -> Self {
+        Self::new(Priorities::default(), Positions::default())
+    }
+}
+
+/// Guard to the priority associated with an index of an `IndexedMinMaxQueue`, which restores the
+/// heap property when dropped.
+pub struct MinMaxMutRef<'a, Index, Priorities, Positions, C = MinOrder>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index>,
+    C: Compare<Priorities::Output>,
+    Positions: Indexed<Index = Index, Output = usize>,
+{
+    queue: &'a mut IndexedMinMaxQueue<Index, Priorities, Positions, C>,
+    index: Index,
+}
+
+impl<'a, Index, Priorities, Positions, C> Deref
+    for MinMaxMutRef<'a, Index, Priorities, Positions, C>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index>,
+    C: Compare<Priorities::Output>,
+    Positions: Indexed<Index = Index, Output = usize>,
+{
+    type Target = Priorities::Output;
+
+    fn deref(&self) -> &Self::Target {
+        self.queue.priorities.index(self.index)
+    }
+}
+
+impl<'a, Index, Priorities, Positions, C> DerefMut
+    for MinMaxMutRef<'a, Index, Priorities, Positions, C>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index>,
+    C: Compare<Priorities::Output>,
+    Positions: Indexed<Index = Index, Output = usize>,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.queue.priorities.index_mut(self.index)
+    }
+}
+
+impl<'a, Index, Priorities, Positions, C> Drop for MinMaxMutRef<'a, Index, Priorities, Positions, C>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index>,
+    C: Compare<Priorities::Output>,
+    Positions: Indexed<Index = Index, Output = usize>,
+{
+    fn drop(&mut self) {
+        if let Some(&position) = self.queue.positions.get(self.index) {
+            self.queue.sift(position);
+        }
+    }
+}
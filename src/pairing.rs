@@ -0,0 +1,510 @@
+use crate::compare::{Compare, MinOrder};
+use crate::indexed::Indexed;
+use crate::Error;
+use std::mem;
+use std::ops::{Deref, DerefMut};
+
+/// Sentinel for the absence of a node.
+const NIL: usize = usize::MAX;
+
+/// A node of the pairing heap, stored in an arena and linked by arena slots.
+#[derive(Debug, Copy, Clone)]
+struct Node<Index> {
+    /// The index stored in this node.
+    index: Index,
+    /// The first child of this node.
+    child: usize,
+    /// The next sibling of this node.
+    sibling: usize,
+    /// The previous sibling of this node, or its parent if it is the first child.
+    prev: usize,
+}
+
+/// Indexed Priority Queue backed by a pairing heap.
+///
+/// Offers the same index-based operations as [`IndexedPriorityQueue`](crate::IndexedPriorityQueue),
+/// but `update_down` runs in `O(1)` amortized time, which pays off for decrease-key heavy workloads
+/// such as Dijkstra on dense graphs. The positions map stores the arena slot of the node of each index.
+#[derive(Debug)]
+pub struct PairingIPQ<Index, Priorities, Positions, C = MinOrder>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index>,
+    C: Compare<Priorities::Output>,
+    Positions: Indexed<Index = Index, Output = usize>,
+{
+    /// The priorities associated with indexes on the heap.
+    priorities: Priorities,
+    /// The arena slots of the nodes associated with indexes on the heap.
+    positions: Positions,
+    /// The arena storing the nodes of the heap.
+    nodes: Vec<Node<Index>>,
+    /// The arena slot of the root node, or `NIL` if the heap is empty.
+    root: usize,
+    /// Scratch space used while merging the children of a removed node.
+    pairs: Vec<usize>,
+    /// The comparator defining the order of the priorities.
+    compare: C,
+}
+
+impl<Index, Priorities, Positions, C> PairingIPQ<Index, Priorities, Positions, C>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index>,
+    C: Compare<Priorities::Output>,
+    Positions: Indexed<Index = Index, Output = usize>,
+{
+    /// Constructs a new, empty `PairingIPQ`.
+    pub fn new(priorities: impl Into<Priorities>, positions: impl Into<Positions>) -> Self
+    where
+        C: Default,
+    {
+        Self::with_comparator(priorities, positions, C::default())
+    }
+
+    /// Constructs a new, empty `PairingIPQ` that orders priorities using the given comparator.
+    pub fn with_comparator(
+        priorities: impl Into<Priorities>,
+        positions: impl Into<Positions>,
+        compare: C,
+    ) -> Self {
+        Self {
+            priorities: priorities.into(),
+            positions: positions.into(),
+            nodes: Vec::new(),
+            root: NIL,
+            pairs: Vec::new(),
+            compare,
+        }
+    }
+
+    /// Returns the number of indices in the queue.
+    ///
+    /// Time complexity: `O(1)`
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns `true` if the queue contains no indices.
+    ///
+    /// Time complexity: `O(1)`
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Returns `true` if the queue contains the specified index.
+    ///
+    /// Time complexity: `O(1)`
+    pub fn contains(&self, index: Index) -> bool {
+        self.positions.contains(index)
+    }
+
+    /// Returns the index associated with the smallest priority in the queue, or `None` if it is empty.
+    ///
+    /// Time complexity: `O(1)`
+    pub fn min(&self) -> Option<&Index> {
+        self.nodes.get(self.root).map(|node| &node.index)
+    }
+
+    /// Returns the smallest priority in the queue, or `None`, if it is empty.
+    ///
+    /// Time complexity: `O(1)`
+    pub fn min_priority(&self) -> Option<&Priorities::Output> {
+        self.min().and_then(|index| self.priorities.get(*index))
+    }
+
+    /// Returns the priority associated with the specified index, or `None`, if the index has no priority.
+    ///
+    /// Time complexity: `O(1)`
+    pub fn get_priority(&self, index: Index) -> Option<&Priorities::Output> {
+        self.priorities.get(index)
+    }
+
+    /// Inserts an index-priority pair into the queue.
+    /// Returns the previous priority associated with the index, if it existed.
+    ///
+    /// Time complexity: `O(1)`, or `O(log n)` amortized if the index is already in the queue
+    pub fn push(&mut self, index: Index, value: Priorities::Output) -> Option<Priorities::Output> {
+        match self.positions.get(index) {
+            Some(&node) => {
+                let old_priority = mem::replace(self.priorities.index_mut(index), value);
+                self.reposition(node);
+                Some(old_priority)
+            }
+            None => {
+                let old_priority = self.priorities.insert(index, value);
+                self.restore_index(index);
+                old_priority
+            }
+        }
+    }
+
+    /// Reinserts a previously removed index into the queue with its last associated value.
+    ///
+    /// Time complexity: `O(1)`
+    pub fn restore_index(&mut self, index: Index) {
+        if !self.positions.contains(index) {
+            let node = self.nodes.len();
+            self.nodes.push(Node {
+                index,
+                child: NIL,
+                sibling: NIL,
+                prev: NIL,
+            });
+            self.positions.insert(index, node);
+            self.root = self.meld(self.root, node);
+        }
+    }
+
+    /// Removes and returns the index associated with the smallest priority in the queue, or `None` if it is empty.
+    ///
+    /// Time complexity: `O(log n)` amortized
+    pub fn pop(&mut self) -> Option<Index> {
+        let index = *self.min()?;
+        self.remove_node(self.root);
+        Some(index)
+    }
+
+    /// Removes the specified index and its associated priority from the queue.
+    /// Returns the removed priority.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index has no associated priority.
+    ///
+    /// Time complexity: `O(log n)` amortized
+    pub fn remove(&mut self, index: Index) -> Priorities::Output {
+        self.remove_index(index);
+        self.priorities.remove(index).unwrap()
+    }
+
+    /// Removes the specified index from the queue, retaining its associated priority.
+    ///
+    /// Time complexity: `O(log n)` amortized
+    pub fn remove_index(&mut self, index: Index) {
+        if let Some(&node) = self.positions.get(index) {
+            self.remove_node(node);
+        }
+    }
+
+    /// Clears all indices and their priorities from the queue.
+    pub fn clear(&mut self) {
+        self.clear_indices();
+        self.priorities.clear();
+    }
+
+    /// Clears all indices from the queue.
+    ///
+    /// Time complexity: `O(n)`
+    pub fn clear_indices(&mut self) {
+        for node in &self.nodes {
+            self.positions.remove(node.index);
+        }
+        self.nodes.clear();
+        self.root = NIL;
+    }
+
+    /// Increases the priority associated with the specified index.
+    /// Pairing heaps have no faster path for increases, so this is equivalent to `update_dyn`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index has no associated priority.
+    ///
+    /// Time complexity: `O(log n)` amortized
+    pub fn update_up(
+        &mut self,
+        index: Index,
+    ) -> PairingMutRef<'_, Index, Priorities, Positions, C> {
+        self.try_update(index, Direction::Any).unwrap()
+    }
+
+    /// Decreases the priority associated with the specified index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index has no associated priority.
+    ///
+    /// Time complexity: `O(1)` amortized
+    pub fn update_down(
+        &mut self,
+        index: Index,
+    ) -> PairingMutRef<'_, Index, Priorities, Positions, C> {
+        self.try_update(index, Direction::Down).unwrap()
+    }
+
+    /// Modifies the priority associated with the specified index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index has no associated priority.
+    ///
+    /// Time complexity: `O(log n)` amortized
+    pub fn update_dyn(
+        &mut self,
+        index: Index,
+    ) -> PairingMutRef<'_, Index, Priorities, Positions, C> {
+        self.try_update(index, Direction::Any).unwrap()
+    }
+
+    /// Returns a guard to the priority associated with the specified index, or an error if it has no priority.
+    fn try_update(
+        &mut self,
+        index: Index,
+        direction: Direction,
+    ) -> Result<PairingMutRef<'_, Index, Priorities, Positions, C>, Error> {
+        if !self.priorities.contains(index) {
+            return Err(Error::MissingPriority);
+        }
+
+        Ok(PairingMutRef {
+            queue: self,
+            index,
+            direction,
+        })
+    }
+
+    /// Compares the priorities of two nodes.
+    fn less(&self, a: usize, b: usize) -> bool {
+        self.compare
+            .compare(
+                self.priorities.index(self.nodes[a].index),
+                self.priorities.index(self.nodes[b].index),
+            )
+            .is_lt()
+    }
+
+    /// Melds two heaps given by their roots and returns the root of the result.
+    fn meld(&mut self, a: usize, b: usize) -> usize {
+        if a == NIL {
+            return b;
+        }
+        if b == NIL {
+            return a;
+        }
+
+        let (parent, child) = if self.less(b, a) { (b, a) } else { (a, b) };
+        let first_child = self.nodes[parent].child;
+        if first_child != NIL {
+            self.nodes[first_child].prev = child;
+        }
+        self.nodes[child].sibling = first_child;
+        self.nodes[child].prev = parent;
+        self.nodes[parent].child = child;
+        parent
+    }
+
+    /// Detaches a non-root node, together with its subtree, from its parent and siblings.
+    fn detach(&mut self, node: usize) {
+        let Node { prev, sibling, .. } = self.nodes[node];
+        if self.nodes[prev].child == node {
+            self.nodes[prev].child = sibling;
+        } else {
+            self.nodes[prev].sibling = sibling;
+        }
+        if sibling != NIL {
+            self.nodes[sibling].prev = prev;
+        }
+        self.nodes[node].prev = NIL;
+        self.nodes[node].sibling = NIL;
+    }
+
+    /// Merges a list of siblings into a single heap using the two-pass strategy, and returns its root.
+    fn merge_pairs(&mut self, first: usize) -> usize {
+        let mut pairs = mem::take(&mut self.pairs);
+
+        // First pass: meld pairs of siblings from left to right.
+        let mut current = first;
+        while current != NIL {
+            let a = current;
+            let b = self.nodes[a].sibling;
+            self.nodes[a].prev = NIL;
+            self.nodes[a].sibling = NIL;
+            if b == NIL {
+                pairs.push(a);
+                break;
+            }
+            current = self.nodes[b].sibling;
+            self.nodes[b].prev = NIL;
+            self.nodes[b].sibling = NIL;
+            pairs.push(self.meld(a, b));
+        }
+
+        // Second pass: meld the resulting heaps from right to left.
+        let mut root = NIL;
+        while let Some(node) = pairs.pop() {
+            root = self.meld(node, root);
+        }
+
+        self.pairs = pairs;
+        root
+    }
+
+    /// Moves a node whose priority was decreased to the root list and melds it with the root.
+    fn decrease_key(&mut self, node: usize) {
+        if node != self.root {
+            self.detach(node);
+            self.root = self.meld(self.root, node);
+        }
+    }
+
+    /// Restores the heap property for a node whose priority was changed arbitrarily.
+    fn reposition(&mut self, node: usize) {
+        let children = mem::replace(&mut self.nodes[node].child, NIL);
+        let merged = self.merge_pairs(children);
+        if node == self.root {
+            self.root = self.meld(merged, node);
+        } else {
+            self.detach(node);
+            self.root = self.meld(self.root, merged);
+            self.root = self.meld(self.root, node);
+        }
+    }
+
+    /// Removes a node from the heap and frees its arena slot.
+    fn remove_node(&mut self, node: usize) {
+        let children = mem::replace(&mut self.nodes[node].child, NIL);
+        let merged = self.merge_pairs(children);
+        if node == self.root {
+            self.root = merged;
+        } else {
+            self.detach(node);
+            self.root = self.meld(self.root, merged);
+        }
+
+        self.positions.remove(self.nodes[node].index);
+        self.free(node);
+    }
+
+    /// Frees the arena slot of a detached node by moving the last node into it.
+    fn free(&mut self, node: usize) {
+        let last = self.nodes.len() - 1;
+        self.nodes.swap_remove(node);
+        if node == last {
+            return;
+        }
+
+        let Node {
+            index,
+            child,
+            sibling,
+            prev,
+        } = self.nodes[node];
+        *self.positions.index_mut(index) = node;
+        if child != NIL {
+            self.nodes[child].prev = node;
+        }
+        if sibling != NIL {
+            self.nodes[sibling].prev = node;
+        }
+        if prev != NIL {
+            if self.nodes[prev].child == last {
+                self.nodes[prev].child = node;
+            } else {
+                self.nodes[prev].sibling = node;
+            }
+        }
+        if self.root == last {
+            self.root = node;
+        }
+    }
+
+    /// Returns `true` if no child of the node has a smaller priority than the node itself.
+    #[cfg(any(debug_assertions, feature = "checked"))]
+    fn le_children(&self, node: usize) -> bool {
+        let mut child = self.nodes[node].child;
+        while child != NIL {
+            if self.less(child, node) {
+                return false;
+            }
+            child = self.nodes[child].sibling;
+        }
+        true
+    }
+}
+
+impl<Index, Priorities, Positions, C> Default for PairingIPQ<Index, Priorities, Positions, C>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index> + Default,
+    C: Compare<Priorities::Output> + Default,
+    Positions: Indexed<Index = Index, Output = usize> + Default,
+{
+    fn default() -> Self {
+        Self::new(Priorities::default(), Positions::default())
+    }
+}
+
+/// The direction in which a priority is allowed to change through a `PairingMutRef`.
+#[derive(Debug, Copy, Clone)]
+enum Direction {
+    Down,
+    Any,
+}
+
+/// Guard to the priority associated with an index of a `PairingIPQ`, which restores the heap
+/// property when dropped.
+pub struct PairingMutRef<'a, Index, Priorities, Positions, C = MinOrder>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index>,
+    C: Compare<Priorities::Output>,
+    Positions: Indexed<Index = Index, Output = usize>,
+{
+    queue: &'a mut PairingIPQ<Index, Priorities, Positions, C>,
+    index: Index,
+    direction: Direction,
+}
+
+impl<'a, Index, Priorities, Positions, C> Deref
+    for PairingMutRef<'a, Index, Priorities, Positions, C>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index>,
+    C: Compare<Priorities::Output>,
+    Positions: Indexed<Index = Index, Output = usize>,
+{
+    type Target = Priorities::Output;
+
+    fn deref(&self) -> &Self::Target {
+        self.queue.priorities.index(self.index)
+    }
+}
+
+impl<'a, Index, Priorities, Positions, C> DerefMut
+    for PairingMutRef<'a, Index, Priorities, Positions, C>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index>,
+    C: Compare<Priorities::Output>,
+    Positions: Indexed<Index = Index, Output = usize>,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.queue.priorities.index_mut(self.index)
+    }
+}
+
+impl<'a, Index, Priorities, Positions, C> Drop
+    for PairingMutRef<'a, Index, Priorities, Positions, C>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index>,
+    C: Compare<Priorities::Output>,
+    Positions: Indexed<Index = Index, Output = usize>,
+{
+    fn drop(&mut self) {
+        if let Some(&node) = self.queue.positions.get(self.index) {
+            match self.direction {
+                Direction::Down => {
+                    #[cfg(any(debug_assertions, feature = "checked"))]
+                    assert!(
+                        self.queue.le_children(node),
+                        "`update_down` must not increase the priority"
+                    );
+                    self.queue.decrease_key(node);
+                }
+                Direction::Any => self.queue.reposition(node),
+            }
+        }
+    }
+}
@@ -0,0 +1,374 @@
+use crate::indexed::Indexed;
+use std::mem;
+use std::ops::{Deref, DerefMut};
+
+/// Unsigned integer priorities that can be stored in a [`RadixIPQ`].
+pub trait RadixKey: Copy + Ord {
+    /// The smallest key.
+    const MIN: Self;
+    /// The number of buckets needed for keys of this type.
+    const BUCKETS: usize;
+
+    /// Returns the bucket of this key relative to the last popped key:
+    /// `0` if they are equal, otherwise one more than the position of the highest differing bit.
+    fn bucket(self, last: Self) -> usize;
+}
+
+macro_rules! impl_radix_key {
+    ($($t:ty),*) => {
+        $(
+            impl RadixKey for $t {
+                const MIN: Self = 0;
+                const BUCKETS: usize = <$t>::BITS as usize + 1;
+
+                fn bucket(self, last: Self) -> usize {
+                    (<$t>::BITS - (self ^ last).leading_zeros()) as usize
+                }
+            }
+        )*
+    };
+}
+
+impl_radix_key!(u8, u16, u32, u64, u128, usize);
+
+/// Indexed Priority Queue backed by a radix heap.
+///
+/// The queue is specialised for monotone workloads with unsigned integer priorities, such as Dijkstra:
+/// priorities that are pushed, restored or updated must never be smaller than the last popped priority.
+/// This is checked in debug builds and when the `checked` feature is enabled.
+///
+/// Indices are kept in buckets based on the highest bit in which their priority differs from the last
+/// popped priority. The positions map stores the offset of each index within its bucket.
+#[derive(Debug)]
+pub struct RadixIPQ<Index, Priorities, Positions>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index, Output: RadixKey>,
+    Positions: Indexed<Index = Index, Output = usize>,
+{
+    /// The priorities associated with indexes on the heap.
+    priorities: Priorities,
+    /// The offsets of the indexes within their buckets.
+    positions: Positions,
+    /// The buckets storing the indexes, see [`RadixKey::bucket`].
+    buckets: Vec<Vec<Index>>,
+    /// The last popped priority.
+    last: Priorities::Output,
+    /// The number of indices in the queue.
+    len: usize,
+}
+
+impl<Index, Priorities, Positions> RadixIPQ<Index, Priorities, Positions>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index, Output: RadixKey>,
+    Positions: Indexed<Index = Index, Output = usize>,
+{
+    /// Constructs a new, empty `RadixIPQ`.
+    pub fn new(priorities: impl Into<Priorities>, positions: impl Into<Positions>) -> Self {
+        Self {
+            priorities: priorities.into(),
+            positions: positions.into(),
+            buckets: (0..Priorities::Output::BUCKETS)
+                .map(|_| Vec::new())
+                .collect(),
+            last: Priorities::Output::MIN,
+            len: 0,
+        }
+    }
+
+    /// Returns the number of indices in the queue.
+    ///
+    /// Time complexity: `O(1)`
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the queue contains no indices.
+    ///
+    /// Time complexity: `O(1)`
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if the queue contains the specified index.
+    ///
+    /// Time complexity: `O(1)`
+    pub fn contains(&self, index: Index) -> bool {
+        self.positions.contains(index)
+    }
+
+    /// Returns the last popped priority, which is a lower bound for all priorities in the queue.
+    ///
+    /// Time complexity: `O(1)`
+    pub fn last_popped(&self) -> Priorities::Output {
+        self.last
+    }
+
+    /// Returns the priority associated with the specified index, or `None`, if the index has no priority.
+    ///
+    /// Time complexity: `O(1)`
+    pub fn get_priority(&self, index: Index) -> Option<&Priorities::Output> {
+        self.priorities.get(index)
+    }
+
+    /// Returns the index associated with the smallest priority in the queue, or `None` if it is empty.
+    ///
+    /// Time complexity: `O(1)` if the smallest priority equals the last popped priority, `O(n)` otherwise
+    pub fn min(&self) -> Option<&Index> {
+        if let Some(index) = self.buckets[0].last() {
+            return Some(index);
+        }
+
+        self.buckets
+            .iter()
+            .find(|bucket| !bucket.is_empty())?
+            .iter()
+            .min_by_key(|index| *self.priorities.index(**index))
+    }
+
+    /// Returns the smallest priority in the queue, or `None`, if it is empty.
+    ///
+    /// Time complexity: see [`RadixIPQ::min`]
+    pub fn min_priority(&self) -> Option<&Priorities::Output> {
+        self.min().map(|index| self.priorities.index(*index))
+    }
+
+    /// Inserts an index-priority pair into the queue.
+    /// Returns the previous priority associated with the index, if it existed.
+    ///
+    /// Time complexity: `O(1)`
+    pub fn push(&mut self, index: Index, value: Priorities::Output) -> Option<Priorities::Output> {
+        self.check_monotone(value);
+
+        if self.positions.contains(index) {
+            let old_priority = *self.priorities.index(index);
+            self.remove_from_bucket(index, old_priority);
+            self.priorities.insert(index, value);
+            self.insert_into_bucket(index, value);
+            Some(old_priority)
+        } else {
+            let old_priority = self.priorities.insert(index, value);
+            self.restore_index(index);
+            old_priority
+        }
+    }
+
+    /// Reinserts a previously removed index into the queue with its last associated value.
+    ///
+    /// Time complexity: `O(1)`
+    pub fn restore_index(&mut self, index: Index) {
+        if !self.positions.contains(index) {
+            let priority = *self.priorities.index(index);
+            self.check_monotone(priority);
+            self.insert_into_bucket(index, priority);
+            self.len += 1;
+        }
+    }
+
+    /// Removes and returns the index associated with the smallest priority in the queue, or `None` if it is empty.
+    ///
+    /// Time complexity: `O(log C)` amortized, where `C` is the largest priority
+    pub fn pop(&mut self) -> Option<Index> {
+        if self.is_empty() {
+            return None;
+        }
+
+        // Redistribute the first non-empty bucket relative to its smallest priority.
+        if self.buckets[0].is_empty() {
+            let bucket = self.buckets.iter().position(|bucket| !bucket.is_empty())?;
+            let indices = mem::take(&mut self.buckets[bucket]);
+            self.last = indices
+                .iter()
+                .map(|index| *self.priorities.index(*index))
+                .min()?;
+            for index in indices {
+                self.insert_into_bucket(index, *self.priorities.index(index));
+            }
+        }
+
+        let index = self.buckets[0].pop()?;
+        self.positions.remove(index);
+        self.len -= 1;
+        Some(index)
+    }
+
+    /// Removes the specified index and its associated priority from the queue.
+    /// Returns the removed priority.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index has no associated priority.
+    ///
+    /// Time complexity: `O(1)`
+    pub fn remove(&mut self, index: Index) -> Priorities::Output {
+        self.remove_index(index);
+        self.priorities.remove(index).unwrap()
+    }
+
+    /// Removes the specified index from the queue, retaining its associated priority.
+    ///
+    /// Time complexity: `O(1)`
+    pub fn remove_index(&mut self, index: Index) {
+        if self.positions.contains(index) {
+            self.remove_from_bucket(index, *self.priorities.index(index));
+            self.len -= 1;
+        }
+    }
+
+    /// Clears all indices and their priorities from the queue, and resets the last popped priority.
+    pub fn clear(&mut self) {
+        self.clear_indices();
+        self.priorities.clear();
+    }
+
+    /// Clears all indices from the queue, and resets the last popped priority.
+    ///
+    /// Time complexity: `O(n)`
+    pub fn clear_indices(&mut self) {
+        for &index in self.buckets.iter().flatten() {
+            self.positions.remove(index);
+        }
+        self.buckets.iter_mut().for_each(Vec::clear);
+        self.last = Priorities::Output::MIN;
+        self.len = 0;
+    }
+
+    /// Decreases the priority associated with the specified index.
+    /// The new priority must not be smaller than the last popped priority.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index has no associated priority.
+    ///
+    /// Time complexity: `O(1)`
+    pub fn update_down(&mut self, index: Index) -> RadixMutRef<'_, Index, Priorities, Positions> {
+        self.update(index, true)
+    }
+
+    /// Modifies the priority associated with the specified index.
+    /// The new priority must not be smaller than the last popped priority.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index has no associated priority.
+    ///
+    /// Time complexity: `O(1)`
+    pub fn update_dyn(&mut self, index: Index) -> RadixMutRef<'_, Index, Priorities, Positions> {
+        self.update(index, false)
+    }
+
+    /// Returns a guard to the priority associated with the specified index.
+    fn update(
+        &mut self,
+        index: Index,
+        _decrease: bool,
+    ) -> RadixMutRef<'_, Index, Priorities, Positions> {
+        RadixMutRef {
+            old_value: *self.priorities.index(index),
+            queue: self,
+            index,
+            #[cfg(any(debug_assertions, feature = "checked"))]
+            decrease: _decrease,
+        }
+    }
+
+    /// Panics if the priority is smaller than the last popped priority,
+    /// in debug builds or when the `checked` feature is enabled.
+    fn check_monotone(&self, _priority: Priorities::Output) {
+        #[cfg(any(debug_assertions, feature = "checked"))]
+        assert!(
+            _priority >= self.last,
+            "priorities must not be smaller than the last popped priority"
+        );
+    }
+
+    /// Appends the index to the bucket of the given priority.
+    fn insert_into_bucket(&mut self, index: Index, priority: Priorities::Output) {
+        let bucket = &mut self.buckets[priority.bucket(self.last)];
+        self.positions.insert(index, bucket.len());
+        bucket.push(index);
+    }
+
+    /// Removes the index from the bucket of the given priority.
+    fn remove_from_bucket(&mut self, index: Index, priority: Priorities::Output) {
+        let offset = self.positions.remove(index).unwrap();
+        let bucket = &mut self.buckets[priority.bucket(self.last)];
+        bucket.swap_remove(offset);
+        if let Some(moved) = bucket.get(offset) {
+            *self.positions.index_mut(*moved) = offset;
+        }
+    }
+}
+
+impl<Index, Priorities, Positions> Default for RadixIPQ<Index, Priorities, Positions>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index, Output: RadixKey> + Default,
+    Positions: Indexed<Index = Index, Output = usize> + Default,
+{
+    fn default() -> Self {
+        Self::new(Priorities::default(), Positions::default())
+    }
+}
+
+/// Guard to the priority associated with an index of a `RadixIPQ`, which moves the index to its
+/// new bucket when dropped.
+pub struct RadixMutRef<'a, Index, Priorities, Positions>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index, Output: RadixKey>,
+    Positions: Indexed<Index = Index, Output = usize>,
+{
+    queue: &'a mut RadixIPQ<Index, Priorities, Positions>,
+    index: Index,
+    old_value: Priorities::Output,
+    /// Whether the priority may only decrease, which is checked when the guard is dropped.
+    #[cfg(any(debug_assertions, feature = "checked"))]
+    decrease: bool,
+}
+
+impl<'a, Index, Priorities, Positions> Deref for RadixMutRef<'a, Index, Priorities, Positions>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index, Output: RadixKey>,
+    Positions: Indexed<Index = Index, Output = usize>,
+{
+    type Target = Priorities::Output;
+
+    fn deref(&self) -> &Self::Target {
+        self.queue.priorities.index(self.index)
+    }
+}
+
+impl<'a, Index, Priorities, Positions> DerefMut for RadixMutRef<'a, Index, Priorities, Positions>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index, Output: RadixKey>,
+    Positions: Indexed<Index = Index, Output = usize>,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.queue.priorities.index_mut(self.index)
+    }
+}
+
+impl<'a, Index, Priorities, Positions> Drop for RadixMutRef<'a, Index, Priorities, Positions>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index, Output: RadixKey>,
+    Positions: Indexed<Index = Index, Output = usize>,
+{
+    fn drop(&mut self) {
+        if self.queue.positions.contains(self.index) {
+            let new_value = *self.queue.priorities.index(self.index);
+            #[cfg(any(debug_assertions, feature = "checked"))]
+            assert!(
+                !self.decrease || new_value <= self.old_value,
+                "`update_down` must not increase the priority"
+            );
+            self.queue.check_monotone(new_value);
+            self.queue.remove_from_bucket(self.index, self.old_value);
+            self.queue.insert_into_bucket(self.index, new_value);
+        }
+    }
+}
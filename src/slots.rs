@@ -0,0 +1,95 @@
+use std::mem;
+
+/// Array whose slots are live only while they are stamped with the current generation.
+/// Advancing the generation clears all slots at once.
+///
+/// The stamps are kept apart from the values, so that small values are not padded to the
+/// alignment of the stamps.
+#[derive(Debug)]
+pub(crate) struct Slots<T> {
+    /// The values of the slots, which remain stored after their slot is unset.
+    values: Vec<T>,
+    /// The generation in which each slot was set, `0` if unset.
+    generations: Vec<u32>,
+    /// The current generation, never `0`.
+    generation: u32,
+}
+
+impl<T> Slots<T> {
+    /// Constructs slots from their values, where `live` decides which values are set.
+    pub fn new(values: Vec<T>, live: impl Fn(&T) -> bool) -> Self {
+        let generations = values.iter().map(|value| u32::from(live(value))).collect();
+        Self {
+            values,
+            generations,
+            generation: 1,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        (*self.generations.get(index)? == self.generation).then(|| &self.values[index])
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        (*self.generations.get(index)? == self.generation).then(|| &mut self.values[index])
+    }
+
+    /// Sets the value of the slot. Returns the previous value, if the slot was set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of range.
+    pub fn set(&mut self, index: usize, value: T) -> Option<T> {
+        let old_value = mem::replace(&mut self.values[index], value);
+        (mem::replace(&mut self.generations[index], self.generation) == self.generation)
+            .then_some(old_value)
+    }
+
+    /// Unsets the slot. Returns its value, which remains stored until the slot is set again,
+    /// if the slot was set.
+    pub fn unset(&mut self, index: usize) -> Option<&mut T> {
+        let generation = self.generations.get_mut(index)?;
+        (mem::replace(generation, 0) == self.generation).then(|| &mut self.values[index])
+    }
+
+    /// Unsets all slots.
+    ///
+    /// Time complexity: `O(1)`, except for every `u32::MAX`-th call, which resets all stamps in `O(n)`
+    pub fn clear(&mut self) {
+        if self.generation == u32::MAX {
+            self.generations.fill(0);
+            self.generation = 0;
+        }
+        self.generation += 1;
+    }
+
+    /// Grows the array to `n` unset slots with the given value.
+    ///
+    /// Time complexity: amortized `O(1)` per added slot
+    pub fn grow(&mut self, n: usize, value: impl FnMut() -> T) {
+        if n > self.len() {
+            self.values.resize_with(n, value);
+            self.generations.resize(n, 0);
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.values
+            .iter()
+            .zip(&self.generations)
+            .filter(|(_, generation)| **generation == self.generation)
+            .map(|(value, _)| value)
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.values
+            .iter_mut()
+            .zip(&self.generations)
+            .filter(|(_, generation)| **generation == self.generation)
+            .map(|(value, _)| value)
+    }
+}
@@ -0,0 +1,98 @@
+use std::cmp::Ordering;
+
+/// Defines the order in which indices with equal priorities are popped from a queue.
+///
+/// Every time an index enters the queue, it is assigned a stamp by [`TieBreak::stamp`].
+/// When two priorities are equal, the index whose stamp compares as `Ordering::Less` is popped first.
+/// Updating the priority of a queued index retains its stamp.
+pub trait TieBreak {
+    type Stamp: Copy;
+
+    /// Returns the stamp for an index that enters the queue.
+    fn stamp(&mut self) -> Self::Stamp;
+
+    /// Compares the stamps of two indices with equal priorities.
+    fn compare(&self, a: &Self::Stamp, b: &Self::Stamp) -> Ordering;
+}
+
+/// Leaves the order of equal priorities unspecified, which depends on the shape of the heap.
+#[derive(Default, Debug, Copy, Clone)]
+pub struct NoTieBreak;
+
+/// Pops indices with equal priorities in the order in which they entered the queue.
+#[derive(Default, Debug, Copy, Clone)]
+pub struct Fifo {
+    next: u64,
+}
+
+/// Pops indices with equal priorities in the reverse order in which they entered the queue.
+#[derive(Default, Debug, Copy, Clone)]
+pub struct Lifo {
+    next: u64,
+}
+
+/// Pops indices with equal priorities in a pseudo-random order, which is reproducible for a given seed.
+#[derive(Default, Debug, Copy, Clone)]
+pub struct RandomTieBreak {
+    state: u64,
+}
+
+impl RandomTieBreak {
+    /// Constructs a new `RandomTieBreak` with the given seed.
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+}
+
+impl TieBreak for NoTieBreak {
+    type Stamp = ();
+
+    fn stamp(&mut self) -> Self::Stamp {}
+
+    fn compare(&self, _a: &Self::Stamp, _b: &Self::Stamp) -> Ordering {
+        Ordering::Equal
+    }
+}
+
+impl TieBreak for Fifo {
+    type Stamp = u64;
+
+    fn stamp(&mut self) -> Self::Stamp {
+        self.next += 1;
+        self.next
+    }
+
+    fn compare(&self, a: &Self::Stamp, b: &Self::Stamp) -> Ordering {
+        a.cmp(b)
+    }
+}
+
+impl TieBreak for Lifo {
+    type Stamp = u64;
+
+    fn stamp(&mut self) -> Self::Stamp {
+        self.next += 1;
+        self.next
+    }
+
+    fn compare(&self, a: &Self::Stamp, b: &Self::Stamp) -> Ordering {
+        b.cmp(a)
+    }
+}
+
+impl TieBreak for RandomTieBreak {
+    type Stamp = u64;
+
+    /// Generates the next stamp using SplitMix64.
+    fn stamp(&mut self) -> Self::Stamp {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn compare(&self, a: &Self::Stamp, b: &Self::Stamp) -> Ordering {
+        a.cmp(b)
+    }
+}
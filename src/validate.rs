@@ -0,0 +1,97 @@
+use crate::compare::Compare;
+use crate::indexed::{Indexed, PositionMap};
+use crate::layout::Layout;
+use crate::tie_break::TieBreak;
+use crate::IndexedPriorityQueue;
+use std::fmt::{Display, Formatter};
+
+/// A violation of the internal invariants of an `IndexedPriorityQueue`, as reported by
+/// [`IndexedPriorityQueue::validate`]. Positions refer to locations on the heap.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum InvariantViolation {
+    /// The node at position `child` has a smaller priority than its parent at position `parent`.
+    HeapOrder { parent: usize, child: usize },
+    /// The index at `position` is not mapped back to `position` by the positions map.
+    PositionMismatch { position: usize },
+    /// The index at `position` has no associated priority.
+    MissingPriority { position: usize },
+    /// The positions map contains a different number of indices than the heap.
+    PositionCount { positions: usize, heap: usize },
+}
+
+impl Display for InvariantViolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InvariantViolation::HeapOrder { parent, child } => write!(
+                f,
+                "node at position {child} has a smaller priority than its parent at position {parent}"
+            ),
+            InvariantViolation::PositionMismatch { position } => {
+                write!(f, "index at position {position} has an inconsistent position")
+            }
+            InvariantViolation::MissingPriority { position } => {
+                write!(f, "index at position {position} has no associated priority")
+            }
+            InvariantViolation::PositionCount { positions, heap } => write!(
+                f,
+                "positions map contains {positions} indices, but the heap contains {heap} indices"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for InvariantViolation {}
+
+impl<Index, Priorities, Positions, const D: usize, C, T, L>
+    IndexedPriorityQueue<Index, Priorities, Positions, D, C, T, L>
+where
+    Index: Copy,
+    Priorities: Indexed<Index = Index>,
+    C: Compare<Priorities::Output>,
+    T: TieBreak,
+    L: Layout<Priorities::Output>,
+    Positions: PositionMap<Priorities>,
+{
+    /// Checks the heap property and the consistency between the heap and the positions map.
+    /// Returns the first violation that is found.
+    ///
+    /// Time complexity: `O(n)`
+    pub fn validate(&self) -> Result<(), InvariantViolation> {
+        for (position, &index) in self.heap.iter().enumerate() {
+            if self.position(index) != Some(position) {
+                return Err(InvariantViolation::PositionMismatch { position });
+            }
+            if L::get(&self.stored[position]).is_none() && !self.priorities.contains(index) {
+                return Err(InvariantViolation::MissingPriority { position });
+            }
+            if position > 0 {
+                let parent = Self::parent(position);
+                if self.compare(parent, position).is_gt() {
+                    return Err(InvariantViolation::HeapOrder {
+                        parent,
+                        child: position,
+                    });
+                }
+            }
+        }
+
+        let positions = self.positions.count_positions(&self.priorities);
+        if positions != self.len() {
+            return Err(InvariantViolation::PositionCount {
+                positions,
+                heap: self.len(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Panics if the invariants of the queue are violated, when the `checked` feature is enabled.
+    #[inline]
+    pub(crate) fn check_invariants(&self) {
+        #[cfg(feature = "checked")]
+        if let Err(violation) = self.validate() {
+            panic!("invariant of indexed priority queue violated: {violation}");
+        }
+    }
+}
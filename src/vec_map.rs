@@ -0,0 +1,170 @@
+use crate::compare::Compare;
+use crate::indexed::{Indexed, Position};
+use crate::layout::Layout;
+use crate::tie_break::TieBreak;
+use crate::IndexedPriorityQueue;
+use std::mem;
+
+/// Priorities map backed by a `Vec`, which grows whenever a priority is inserted for an index
+/// past its end. Suited for index spaces that are discovered on the fly.
+#[derive(Debug)]
+pub struct VecPriorityMap<Priority, const N: usize = 0>(Vec<Option<Priority>>);
+
+/// Positions map backed by a `Vec`, which grows whenever a position is inserted for an index
+/// past its end. Positions are stored as `P`, with `P::NONE` marking indices without a position,
+/// see [`Position`].
+///
+/// Clearing the map truncates the `Vec`, which takes constant time.
+#[derive(Debug)]
+pub struct VecPositionMap<const N: usize = 0, P = usize>(Vec<P>);
+
+impl<const OFFSET: usize, P> Default for VecPositionMap<OFFSET, P> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+impl<Priority, const OFFSET: usize> Default for VecPriorityMap<Priority, OFFSET> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+impl<Priority, const OFFSET: usize> Indexed for VecPriorityMap<Priority, OFFSET> {
+    type Index = usize;
+    type Output = Priority;
+
+    fn get(&self, index: Self::Index) -> Option<&Self::Output> {
+        let index = index.checked_sub(OFFSET)?;
+
+        self.0.get(index)?.as_ref()
+    }
+
+    fn get_mut(&mut self, index: Self::Index) -> Option<&mut Self::Output> {
+        let index = index.checked_sub(OFFSET)?;
+
+        self.0.get_mut(index)?.as_mut()
+    }
+
+    fn insert(&mut self, index: Self::Index, value: Self::Output) -> Option<Self::Output> {
+        let index = index.checked_sub(OFFSET).expect("index is out of range");
+
+        if index >= self.0.len() {
+            self.0.resize_with(index + 1, || None);
+        }
+        self.0[index].replace(value)
+    }
+
+    fn remove(&mut self, index: Self::Index) -> Option<Self::Output> {
+        let index = index.checked_sub(OFFSET).expect("index is out of range");
+
+        self.0.get_mut(index)?.take()
+    }
+
+    fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    fn in_range(&self, index: Self::Index) -> bool {
+        index >= OFFSET
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &Self::Output> {
+        self.0.iter().flatten()
+    }
+
+    fn iter_mut(&mut self) -> impl Iterator<Item = &mut Self::Output> {
+        self.0.iter_mut().flatten()
+    }
+}
+
+impl<const OFFSET: usize, P: Position> Indexed for VecPositionMap<OFFSET, P> {
+    type Index = usize;
+    type Output = P;
+
+    fn get(&self, index: Self::Index) -> Option<&Self::Output> {
+        let index = index.checked_sub(OFFSET)?;
+
+        self.0.get(index).filter(|position| **position != P::NONE)
+    }
+
+    fn get_mut(&mut self, index: Self::Index) -> Option<&mut Self::Output> {
+        let index = index.checked_sub(OFFSET)?;
+
+        self.0
+            .get_mut(index)
+            .filter(|position| **position != P::NONE)
+    }
+
+    fn insert(&mut self, index: Self::Index, value: Self::Output) -> Option<Self::Output> {
+        let index = index.checked_sub(OFFSET).expect("index is out of range");
+
+        if index >= self.0.len() {
+            self.0.resize(index + 1, P::NONE);
+        }
+        Some(mem::replace(&mut self.0[index], value)).filter(|position| *position != P::NONE)
+    }
+
+    fn remove(&mut self, index: Self::Index) -> Option<Self::Output> {
+        let index = index.checked_sub(OFFSET).expect("index is out of range");
+
+        Some(mem::replace(self.0.get_mut(index)?, P::NONE)).filter(|position| *position != P::NONE)
+    }
+
+    fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    fn in_range(&self, index: Self::Index) -> bool {
+        index >= OFFSET
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &Self::Output> {
+        self.0.iter().filter(|position| **position != P::NONE)
+    }
+
+    fn iter_mut(&mut self) -> impl Iterator<Item = &mut Self::Output> {
+        self.0.iter_mut().filter(|position| **position != P::NONE)
+    }
+}
+
+impl<Priority, const OFFSET: usize> From<Vec<Priority>> for VecPriorityMap<Priority, OFFSET> {
+    fn from(value: Vec<Priority>) -> Self {
+        Self(value.into_iter().map(Some).collect())
+    }
+}
+
+/// Positions equal to `P::NONE` are unset.
+impl<const OFFSET: usize, P> From<Vec<P>> for VecPositionMap<OFFSET, P> {
+    fn from(value: Vec<P>) -> Self {
+        Self(value)
+    }
+}
+
+impl<Priority, const OFFSET: usize, P, const D: usize, C, T, L>
+    IndexedPriorityQueue<
+        usize,
+        VecPriorityMap<Priority, OFFSET>,
+        VecPositionMap<OFFSET, P>,
+        D,
+        C,
+        T,
+        L,
+    >
+where
+    P: Position,
+    C: Compare<Priority>,
+    T: TieBreak,
+    L: Layout<Priority>,
+{
+    /// Constructs a new, empty queue for the indices `OFFSET..OFFSET + n`, which all start out
+    /// with the priority `default`. Further indices can be pushed at any time.
+    pub fn with_index_space(n: usize, default: Priority) -> Self
+    where
+        Priority: Clone,
+        C: Default,
+        T: Default,
+    {
+        Self::with_capacity(vec![default; n], vec![P::NONE; n], n)
+    }
+}
@@ -0,0 +1,134 @@
+mod common;
+
+use common::lcg;
+use indexed_priority_queue::{ArrayMapBucketIPQ, HashMapBucketIPQ};
+
+#[test]
+fn window_wrap_around() {
+    // With a range of 4, the window 6..=9 wraps around from bucket 2 to bucket 1.
+    let mut pq = HashMapBucketIPQ::<&str>::with_range(4);
+    assert_eq!(pq.min(), None);
+    for (index, priority) in [("a", 6), ("b", 9), ("c", 7), ("d", 8)] {
+        pq.push(index, priority);
+    }
+    assert_eq!(pq.min(), Some(&"a"));
+    assert_eq!(pq.pop(), Some("a"));
+
+    // 10 lies past the end of the window, which therefore moves up to 7 and reuses bucket 2.
+    assert_eq!(pq.push("e", 10), None);
+    assert_eq!(pq.min_priority(), Some(&7));
+    assert_eq!(pq.pop(), Some("c"));
+    assert_eq!(pq.pop(), Some("d"));
+
+    // Lowering "b" moves it from bucket 1 to bucket 0, in front of "e" in bucket 2.
+    *pq.update_down("b") = 8;
+    assert_eq!(pq.pop(), Some("b"));
+
+    // Restoring into an empty queue restarts the window at 10, which wraps around to bucket 1.
+    pq.remove_index("e");
+    assert!(pq.is_empty());
+    pq.restore_index("e");
+    pq.push("f", 12);
+    pq.push("g", 13);
+    assert_eq!(pq.min(), Some(&"e"));
+    let popped = std::iter::from_fn(|| pq.pop()).collect::<Vec<_>>();
+    assert_eq!(popped, ["e", "f", "g"]);
+    assert_eq!(pq.pop(), None);
+}
+
+#[test]
+fn cyclic_reuse() {
+    let mut pq = ArrayMapBucketIPQ::<0>::new(
+        vec![0; 4].into_boxed_slice(),
+        vec![usize::MAX; 4].into_boxed_slice(),
+        3,
+    );
+    pq.push(0, 0);
+    pq.push(1, 2);
+    assert_eq!(pq.pop(), Some(0));
+    pq.push(2, 4);
+    pq.push(3, 3);
+    assert_eq!(pq.pop(), Some(1));
+    pq.remove_index(3);
+    pq.push(0, 5);
+    assert_eq!(pq.pop(), Some(2));
+    pq.restore_index(3);
+    *pq.update_down(0) = 4;
+    assert_eq!(pq.pop(), Some(3));
+    assert_eq!(pq.pop(), Some(0));
+    assert_eq!(pq.pop(), None);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "within `range`")]
+fn push_outside_range() {
+    let mut pq = HashMapBucketIPQ::<&str>::with_range(4);
+    pq.push("a", 5);
+    pq.push("b", 9);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "within `range`")]
+fn push_below_window() {
+    let mut pq = HashMapBucketIPQ::<u32>::with_range(10);
+    pq.push(1, 15);
+    pq.push(2, 24);
+    pq.push(3, 3);
+}
+
+#[test]
+fn randomized() {
+    let n = 200;
+    let range = 11;
+    let mut state = 3;
+    let mut pq = ArrayMapBucketIPQ::<0>::new(
+        vec![0; n].into_boxed_slice(),
+        vec![usize::MAX; n].into_boxed_slice(),
+        range,
+    );
+    let mut reference = vec![None; n];
+    let mut last = 0;
+
+    for step in 0..5000 {
+        let index = lcg(&mut state) as usize % n;
+        match lcg(&mut state) % 6 {
+            0 | 1 => {
+                let priority = last + lcg(&mut state) as usize % range;
+                pq.push(index, priority);
+                reference[index] = Some(priority);
+            }
+            2 => {
+                pq.remove_index(index);
+                reference[index] = None;
+            }
+            3 => {
+                if let Some(priority) = reference[index].as_mut() {
+                    *priority = last + (*priority - last) / 2;
+                    *pq.update_down(index) = *priority;
+                }
+            }
+            4 => {
+                if let Some(priority) = reference[index].as_mut() {
+                    *priority = last + lcg(&mut state) as usize % range;
+                    *pq.update_dyn(index) = *priority;
+                }
+            }
+            _ => {
+                let min = reference.iter().flatten().min().copied();
+                assert_eq!(pq.min_priority().copied(), min);
+                if let Some(index) = pq.pop() {
+                    assert_eq!(reference[index], min);
+                    reference[index] = None;
+                    last = min.unwrap();
+                }
+            }
+        }
+        if step % 1000 == 999 {
+            pq.clear_indices();
+            reference.fill(None);
+        }
+        assert_eq!(pq.len(), reference.iter().flatten().count());
+    }
+}
@@ -0,0 +1,7 @@
+/// Deterministic pseudo-random numbers for the randomized tests.
+pub fn lcg(state: &mut u64) -> u64 {
+    *state = state
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *state >> 33
+}
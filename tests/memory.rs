@@ -0,0 +1,40 @@
+use indexed_priority_queue::array_map::{ArrayPositionMap, Offset};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::mem::size_of;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Allocator that counts the bytes allocated, for measuring the memory taken by the maps.
+struct Counting;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+/// Returns the number of bytes allocated while constructing the value, along with the value.
+fn allocated<T>(f: impl FnOnce() -> T) -> (usize, T) {
+    let before = ALLOCATED.load(Ordering::Relaxed);
+    let value = f();
+    (ALLOCATED.load(Ordering::Relaxed) - before, value)
+}
+
+#[test]
+fn compact_positions_halve_memory() {
+    let n = 1 << 16;
+
+    let (wide, _map) = allocated(|| ArrayPositionMap::<Offset, usize>::with_mapper(n, Offset));
+    let (compact, _map) = allocated(|| ArrayPositionMap::<Offset, u32>::with_mapper(n, Offset));
+    assert_eq!(wide, n * size_of::<usize>());
+    assert_eq!(compact, n * size_of::<u32>());
+}
@@ -0,0 +1,110 @@
+mod common;
+
+use common::lcg;
+use indexed_priority_queue::{ArrayMapMinMaxQueue, HashMapMinMaxQueue};
+
+#[test]
+fn grandchild_swaps() {
+    // The levels alternate between min levels, holding positions 0 and 3..=6,
+    // and max levels, holding positions 1..=2 and 7..=14.
+    let mut pq = HashMapMinMaxQueue::<&str, i32>::default();
+    assert_eq!(pq.min(), None);
+    assert_eq!(pq.max(), None);
+    for (index, priority) in [("a", 10), ("b", 20), ("c", 30)] {
+        pq.push(index, priority);
+    }
+
+    // "d" lands on a min level below "b" and swaps with its grandparent "a" at the root.
+    pq.push("d", 5);
+    assert_eq!(pq.min(), Some(&"d"));
+
+    // "e" lands on a min level, but exceeds its parent "b", so the two swap levels.
+    pq.push("e", 25);
+    assert_eq!(pq.max(), Some(&"c"));
+
+    // "f" exceeds its parent "c" and replaces it as the maximum.
+    pq.push("f", 40);
+    pq.push("g", 35);
+    assert_eq!(pq.max(), Some(&"f"));
+
+    // "h" lands on a max level below "a" and swaps with its grandparent "e".
+    pq.push("h", 50);
+    assert_eq!(pq.max(), Some(&"h"));
+    assert_eq!(pq.max_priority(), Some(&50));
+
+    // Removing the maximum moves the last node down along the largest grandchildren.
+    assert_eq!(pq.pop_max(), Some("h"));
+    assert_eq!(pq.max(), Some(&"f"));
+
+    // Moving the root to the max levels lets its smallest grandchild take its place.
+    *pq.update_dyn("d") = 45;
+    assert_eq!(pq.min(), Some(&"a"));
+    assert_eq!(pq.max(), Some(&"d"));
+
+    pq.remove_index("a");
+    assert!(!pq.contains("a"));
+    assert_eq!(pq.get_priority("a"), Some(&10));
+    assert_eq!(pq.remove("f"), 40);
+    assert!(pq.try_update_dyn("f").is_err());
+    pq.restore_index("a");
+
+    let mut popped = Vec::new();
+    while let (Some(min), Some(max)) = (pq.pop_min(), pq.pop_max()) {
+        popped.push((min, max));
+    }
+    assert_eq!(popped, [("a", "d"), ("b", "g"), ("e", "c")]);
+    assert!(pq.is_empty());
+}
+
+#[test]
+fn randomized() {
+    let n = 200;
+    let mut state = 11;
+    let mut pq = ArrayMapMinMaxQueue::<u64>::new(
+        vec![0; n].into_boxed_slice(),
+        vec![usize::MAX; n].into_boxed_slice(),
+    );
+    let mut reference = vec![None; n];
+
+    for step in 0..10000 {
+        let index = lcg(&mut state) as usize % n;
+        match lcg(&mut state) % 6 {
+            0 | 1 => {
+                let priority = lcg(&mut state) % 1000;
+                pq.push(index, priority);
+                reference[index] = Some(priority);
+            }
+            2 => {
+                pq.remove_index(index);
+                reference[index] = None;
+            }
+            3 => {
+                if let Some(priority) = reference[index].as_mut() {
+                    *priority = lcg(&mut state) % 1000;
+                    *pq.update_dyn(index) = *priority;
+                }
+            }
+            4 => {
+                let min = reference.iter().flatten().min().copied();
+                assert_eq!(pq.min_priority().copied(), min);
+                if let Some(index) = pq.pop_min() {
+                    assert_eq!(reference[index], min);
+                    reference[index] = None;
+                }
+            }
+            _ => {
+                let max = reference.iter().flatten().max().copied();
+                assert_eq!(pq.max_priority().copied(), max);
+                if let Some(index) = pq.pop_max() {
+                    assert_eq!(reference[index], max);
+                    reference[index] = None;
+                }
+            }
+        }
+        if step % 1000 == 999 {
+            pq.clear_indices();
+            reference.fill(None);
+        }
+        assert_eq!(pq.len(), reference.iter().flatten().count());
+    }
+}
@@ -0,0 +1,99 @@
+mod common;
+
+use common::lcg;
+use indexed_priority_queue::{ArrayMapPairingIPQ, HashMapPairingIPQ};
+
+#[test]
+fn push_pop() {
+    let mut pq = HashMapPairingIPQ::<&str, i32>::default();
+    assert_eq!(pq.min(), None);
+    pq.push("a", 1);
+    pq.push("b", 2);
+    pq.push("f", 7);
+    pq.push("g", 4);
+    pq.push("h", 3);
+    assert_eq!(pq.len(), 5);
+    assert_eq!(pq.pop(), Some("a"));
+    assert_eq!(pq.min(), Some(&"b"));
+    assert_eq!(pq.min_priority(), Some(&2));
+    assert_eq!(pq.push("f", 0), Some(7));
+    assert_eq!(pq.pop(), Some("f"));
+    assert_eq!(pq.len(), 3);
+}
+
+#[test]
+fn remove_restore_update() {
+    let mut pq = HashMapPairingIPQ::<&str, i32>::default();
+    for (index, priority) in [("a", 5), ("b", 3), ("c", 8), ("d", 1), ("e", 9)] {
+        pq.push(index, priority);
+    }
+
+    pq.remove_index("b");
+    assert!(!pq.contains("b"));
+    assert_eq!(pq.get_priority("b"), Some(&3));
+    *pq.update_down("b") = 0;
+    pq.restore_index("b");
+    assert_eq!(pq.min(), Some(&"b"));
+
+    *pq.update_up("b") = 10;
+    *pq.update_dyn("e") = 2;
+    *pq.update_down("c") = 4;
+    assert_eq!(pq.remove("d"), 1);
+
+    let mut popped = Vec::new();
+    while let Some(index) = pq.pop() {
+        popped.push(index);
+    }
+    assert_eq!(popped, ["e", "c", "a", "b"]);
+}
+
+#[test]
+fn randomized() {
+    let n = 200;
+    let mut state = 42;
+    let mut pq = ArrayMapPairingIPQ::<u64>::new(
+        vec![0; n].into_boxed_slice(),
+        vec![usize::MAX; n].into_boxed_slice(),
+    );
+    let mut reference = vec![None; n];
+
+    for step in 0..5000 {
+        let index = lcg(&mut state) as usize % n;
+        match lcg(&mut state) % 6 {
+            0 | 1 => {
+                let priority = lcg(&mut state) % 1000;
+                pq.push(index, priority);
+                reference[index] = Some(priority);
+            }
+            2 => {
+                pq.remove_index(index);
+                reference[index] = None;
+            }
+            3 => {
+                if let Some(priority) = reference[index].as_mut() {
+                    *priority /= 2;
+                    *pq.update_down(index) = *priority;
+                }
+            }
+            4 => {
+                if let Some(priority) = reference[index].as_mut() {
+                    *priority = lcg(&mut state) % 1000;
+                    *pq.update_dyn(index) = *priority;
+                }
+            }
+            _ => {
+                let min = reference.iter().flatten().min().copied();
+                assert_eq!(pq.min_priority().copied(), min);
+                if let Some(index) = pq.pop() {
+                    assert_eq!(reference[index], min);
+                    reference[index] = None;
+                }
+            }
+        }
+        if step % 1000 == 999 {
+            pq.clear_indices();
+            reference.fill(None);
+        }
+        assert_eq!(pq.len(), reference.iter().flatten().count());
+    }
+}
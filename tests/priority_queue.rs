@@ -1,7 +1,10 @@
 // Tests adapted from https://github.com/garro95/priority-queue/blob/4eecbd7ffa182a1def25d520dc78acd561032d11/tests/priority_queue.rs.
 // Licensed under MPL-2.0.txt.
 
-use indexed_priority_queue::HashMapIPQ;
+mod common;
+
+use common::lcg;
+use indexed_priority_queue::{DefaultMapIPQ, HashMapIPQ};
 use std::cmp::Reverse;
 
 #[test]
@@ -103,40 +106,46 @@ fn reversed_order() {
     assert_eq!(pq.pop(), Some("a"));
 }
 
-// #[test]
-// fn from_vec() {
-//     let v = vec![("a", 1), ("b", 2), ("f", 7)];
-//     let mut pq: PriorityQueue<_, _> = PriorityQueue::from(v);
-//     assert_eq!(pq.pop(), Some(("f", 7)));
-//     assert_eq!(pq.len(), 2);
-// }
+#[test]
+fn from_vec() {
+    let v = vec![("a", Reverse(1)), ("b", Reverse(2)), ("f", Reverse(7))];
+    let mut pq = HashMapIPQ::<_, _>::from(v);
+    assert_eq!(pq.pop(), Some("f"));
+    assert_eq!(pq.len(), 2);
+}
 
-// #[test]
-// fn from_vec_with_repeated() {
-//     let v = vec![("a", 1), ("b", 2), ("f", 7), ("a", 2)];
-//     let mut pq: PriorityQueue<_, _> = v.into();
-//     assert_eq!(pq.pop(), Some(("f", 7)));
-//     assert_eq!(pq.len(), 2);
-// }
+#[test]
+fn from_vec_with_repeated() {
+    let v = vec![
+        ("a", Reverse(1)),
+        ("b", Reverse(2)),
+        ("f", Reverse(7)),
+        ("a", Reverse(2)),
+    ];
+    let mut pq: HashMapIPQ<_, _> = v.into();
+    assert_eq!(pq.pop(), Some("f"));
+    assert_eq!(pq.len(), 2);
+    assert_eq!(pq.get_priority("a"), Some(&Reverse(2)));
+}
 
-// #[test]
-// fn from_iter() {
-//     use std::iter::FromIterator;
-//
-//     let v = vec![("a", 1), ("b", 2), ("f", 7)];
-//     let mut pq: PriorityQueue<_, _> = PriorityQueue::from_iter(v);
-//     assert_eq!(pq.pop(), Some(("f", 7)));
-//     assert_eq!(pq.len(), 2);
-// }
+#[test]
+fn from_iter() {
+    use std::iter::FromIterator;
 
-// #[test]
-// fn heap_sort() {
-//     type Pq<I, P> = PriorityQueue<I, P>;
-//
-//     let v = vec![("a", 2), ("b", 7), ("f", 1)];
-//     let sorted = (Pq::from(v)).into_sorted_vec();
-//     assert_eq!(sorted.as_slice(), &["b", "a", "f"]);
-// }
+    let v = vec![("a", Reverse(1)), ("b", Reverse(2)), ("f", Reverse(7))];
+    let mut pq = HashMapIPQ::<_, _>::from_iter(v);
+    assert_eq!(pq.pop(), Some("f"));
+    assert_eq!(pq.len(), 2);
+}
+
+#[test]
+fn heap_sort() {
+    type Pq<I, P> = HashMapIPQ<I, P>;
+
+    let v = vec![("a", Reverse(2)), ("b", Reverse(7)), ("f", Reverse(1))];
+    let sorted = (Pq::from(v)).into_sorted_vec();
+    assert_eq!(sorted.as_slice(), &["b", "a", "f"]);
+}
 
 // #[test]
 // fn change_priority_by() {
@@ -151,6 +160,7 @@ fn reversed_order() {
 // }
 
 #[test]
+#[allow(clippy::needless_borrow)]
 fn remove_empty() {
     let mut pq = HashMapIPQ::<&str, Reverse<i32>>::default();
 
@@ -159,6 +169,7 @@ fn remove_empty() {
 }
 
 #[test]
+#[allow(clippy::needless_borrow)]
 fn remove_one() {
     let mut pq = HashMapIPQ::<&str, Reverse<i32>>::default();
 
@@ -168,19 +179,25 @@ fn remove_one() {
     assert!(pq.is_empty());
 }
 
-// #[test]
-// fn remove() {
-//     use std::iter::FromIterator;
-//     type Pq<I, P> = PriorityQueue<I, P>;
-//
-//     let v = vec![("a", 1), ("b", 2), ("f", 7), ("g", 6), ("h", 5)];
-//     let mut pq = Pq::from_iter(v);
-//
-//     pq.remove(&"b").unwrap();
-//     pq.push("b", 2);
-//     pq.remove(&"b");
-//     assert_eq!(["f", "g", "h", "a"], pq.into_sorted_vec().as_slice());
-// }
+#[test]
+fn remove() {
+    use std::iter::FromIterator;
+    type Pq<I, P> = HashMapIPQ<I, P>;
+
+    let v = vec![
+        ("a", Reverse(1)),
+        ("b", Reverse(2)),
+        ("f", Reverse(7)),
+        ("g", Reverse(6)),
+        ("h", Reverse(5)),
+    ];
+    let mut pq = Pq::from_iter(v);
+
+    pq.remove("b");
+    pq.push("b", Reverse(2));
+    pq.remove("b");
+    assert_eq!(["f", "g", "h", "a"], pq.into_sorted_vec().as_slice());
+}
 
 // #[test]
 // fn remove2() {
@@ -216,41 +233,113 @@ fn remove_one() {
 //     }
 // }
 
-// #[test]
-// fn extend() {
-//     let mut pq = PriorityQueue::new();
-//     pq.push("a", 1);
-//     pq.push("b", 2);
-//     pq.push("f", 7);
-//
-//     let v = vec![("c", 4), ("d", 6), ("e", 3)];
-//     pq.extend(v);
-//     assert_eq!(pq.len(), 6);
-//     assert_eq!(
-//         pq.into_sorted_vec().as_slice(),
-//         &["f", "d", "c", "e", "b", "a"]
-//     );
-// }
+#[test]
+fn extend() {
+    let mut pq = HashMapIPQ::<_, _>::default();
+    pq.push("a", Reverse(1));
+    pq.push("b", Reverse(2));
+    pq.push("f", Reverse(7));
 
-// #[test]
-// fn extend_empty() {
-//     let mut pq = PriorityQueue::new();
-//
-//     let v = vec![("c", 4), ("d", 6), ("e", 3)];
-//     pq.extend(v);
-//     assert_eq!(pq.len(), 3);
-//     assert_eq!(pq.into_sorted_vec().as_slice(), &["d", "c", "e"]);
-// }
+    let v = vec![("c", Reverse(4)), ("d", Reverse(6)), ("e", Reverse(3))];
+    pq.extend(v);
+    assert_eq!(pq.len(), 6);
+    assert_eq!(
+        pq.into_sorted_vec().as_slice(),
+        &["f", "d", "c", "e", "b", "a"]
+    );
+}
 
-// #[test]
-// fn iter() {
-//     let mut pq = PriorityQueue::new();
-//     pq.push("a", 1);
-//     pq.push("b", 2);
-//     pq.push("f", 7);
-//
-//     assert_eq!(pq.iter().count(), 3);
-// }
+#[test]
+fn extend_empty() {
+    let mut pq = HashMapIPQ::<_, _>::default();
+
+    let v = vec![("c", Reverse(4)), ("d", Reverse(6)), ("e", Reverse(3))];
+    pq.extend(v);
+    assert_eq!(pq.len(), 3);
+    assert_eq!(pq.into_sorted_vec().as_slice(), &["d", "c", "e"]);
+}
+
+#[test]
+fn extend_indices() {
+    let mut pq = HashMapIPQ::<usize, usize>::from_iter((0..100).map(|i| (i, (i * 37) % 100)));
+    for i in 0..100 {
+        if i % 10 != 0 {
+            pq.remove_index(i);
+        }
+    }
+
+    // Few indices are restored one by one, many indices trigger a rebuild.
+    pq.extend_indices([1, 2, 3]);
+    assert_eq!(pq.len(), 13);
+    pq.extend_indices(0..100);
+    assert_eq!(pq.len(), 100);
+
+    let priorities = std::iter::from_fn(|| pq.pop().map(|i| (i * 37) % 100)).collect::<Vec<_>>();
+    assert_eq!(priorities, (0..100).collect::<Vec<_>>());
+}
+
+#[test]
+fn drain_sorted() {
+    let mut pq = HashMapIPQ::<_, _>::from(vec![("a", 3), ("b", 1), ("c", 2), ("d", 4)]);
+    pq.remove_index("d");
+
+    let mut drain = pq.drain_sorted();
+    assert_eq!(drain.len(), 3);
+    assert_eq!(drain.next(), Some(("b", 1)));
+    drop(drain);
+
+    // Drained indices lose their priorities, while removed indices retain theirs.
+    assert!(pq.is_empty());
+    assert_eq!(pq.get_priority("a"), None);
+    assert_eq!(pq.get_priority("c"), None);
+    assert_eq!(pq.get_priority("d"), Some(&4));
+
+    // Indices queued with the default priority drain with it.
+    let mut pq = DefaultMapIPQ::<u32, u32>::default();
+    pq.restore_index(5);
+    assert_eq!(pq.drain_sorted().next(), Some((5, 0)));
+}
+
+#[test]
+fn drain() {
+    let mut pq = HashMapIPQ::<_, _>::from(vec![("a", 3), ("b", 1), ("c", 2)]);
+
+    let mut drained = pq.drain().collect::<Vec<_>>();
+    drained.sort();
+    assert_eq!(drained, ["a", "b", "c"]);
+    assert!(pq.is_empty());
+    assert!(!pq.contains("a"));
+
+    // Drained indices retain their priorities and can be restored.
+    pq.restore_index("c");
+    pq.restore_index("b");
+    assert_eq!(pq.pop(), Some("b"));
+}
+
+#[test]
+fn iter() {
+    let mut pq = HashMapIPQ::<_, _>::default();
+    pq.push("a", 1);
+    pq.push("b", 2);
+    pq.push("f", 7);
+    pq.remove_index("b");
+
+    assert_eq!(pq.iter().count(), 2);
+
+    let mut entries = (&pq).into_iter().collect::<Vec<_>>();
+    entries.sort();
+    assert_eq!(entries, [("a", &1), ("f", &7)]);
+
+    assert_eq!(pq.indices().next(), Some(&"a"));
+
+    let mut entries = pq.into_iter().collect::<Vec<_>>();
+    entries.sort();
+    assert_eq!(entries, [("a", 1), ("f", 7)]);
+
+    let mut pq = DefaultMapIPQ::<u32, u32>::default();
+    pq.restore_index(5);
+    assert_eq!(pq.into_iter().next(), Some((5, 0)));
+}
 
 // #[test]
 // fn iter_mut() {
@@ -367,3 +456,728 @@ fn remove_one() {
 //
 //     assert_eq!(pq.pop(), Some(('b', 5)));
 // }
+
+#[test]
+fn push_pop_quaternary() {
+    use indexed_priority_queue::hash_map::IndexedHashMap;
+    use indexed_priority_queue::IndexedPriorityQueue;
+
+    let mut pq = IndexedPriorityQueue::<
+        usize,
+        IndexedHashMap<usize, usize>,
+        IndexedHashMap<usize, usize>,
+        4,
+    >::default();
+    for (index, priority) in [5, 3, 8, 1, 9, 2, 7, 4, 6, 0].into_iter().enumerate() {
+        pq.push(index, priority);
+    }
+    *pq.update_down(2) = 0;
+    pq.remove_index(9);
+
+    let mut popped = Vec::new();
+    while let Some(index) = pq.pop() {
+        popped.push(index);
+    }
+    assert_eq!(popped, vec![2, 3, 5, 1, 7, 0, 8, 6, 4]);
+}
+
+#[test]
+fn heapify_array_map() {
+    use indexed_priority_queue::ArrayMapIPQ;
+
+    let priorities = [9, 4, 7, 1, 8, 2, 6, 3, 5, 0];
+    let mut pq = ArrayMapIPQ::<_, 1>::heapify(
+        vec![0; priorities.len()].into_boxed_slice(),
+        vec![usize::MAX; priorities.len()].into_boxed_slice(),
+        (1..).zip(priorities),
+    );
+
+    let mut popped = Vec::new();
+    while let Some(index) = pq.pop() {
+        popped.push(*pq.get_priority(index).unwrap());
+    }
+    assert_eq!(popped, (0..10).collect::<Vec<_>>());
+}
+
+#[test]
+fn entry() {
+    use indexed_priority_queue::entry::Entry;
+
+    let mut pq = HashMapIPQ::<&str, i32>::default();
+    pq.push("a", 3);
+    pq.push("b", 5);
+    pq.push("c", 4);
+    pq.remove_index("c");
+
+    assert!(matches!(pq.entry("a"), Entry::Queued(_)));
+    assert!(matches!(pq.entry("c"), Entry::Retained(_)));
+    assert!(matches!(pq.entry("d"), Entry::Vacant(_)));
+
+    assert_eq!(pq.entry("a").or_insert(10), &3);
+    assert_eq!(pq.entry("c").or_insert(10), &4);
+    assert_eq!(pq.entry("d").or_insert(1), &1);
+    assert_eq!(pq.min(), Some(&"d"));
+
+    if let Entry::Queued(entry) = pq.entry("b") {
+        *entry.update_down() = 0;
+    }
+    assert_eq!(pq.min(), Some(&"b"));
+
+    if let Entry::Retained(mut entry) = pq.entry("c") {
+        *entry.priority_mut() = -1;
+        entry.restore();
+    }
+    assert_eq!(pq.min(), Some(&"c"));
+
+    if let Entry::Queued(entry) = pq.entry("c") {
+        assert_eq!(entry.remove_index().forget(), -1);
+    }
+    assert!(matches!(pq.entry("c"), Entry::Vacant(_)));
+
+    if let Entry::Queued(entry) = pq.entry("a") {
+        assert_eq!(entry.priority(), &3);
+        *entry.update_down() = -2;
+    }
+    if let Entry::Vacant(entry) = pq.entry("e") {
+        let entry = entry.insert(-3);
+        assert_eq!(entry.priority(), &-3);
+        assert_eq!(*entry.update_dyn(), -3);
+    }
+    assert_eq!(pq.into_sorted_vec(), ["e", "a", "b", "d"]);
+
+    // Every index has a priority in a default map, so unseen indices are retained.
+    let mut pq = DefaultMapIPQ::<u32, u32>::default();
+    match pq.entry(5) {
+        Entry::Retained(entry) => assert_eq!(entry.forget(), 0),
+        _ => panic!("expected a retained entry"),
+    }
+    assert_eq!(pq.entry(5).or_insert(3), &0);
+}
+
+#[test]
+fn fallible() {
+    use indexed_priority_queue::{ArrayMapIPQ, Error};
+
+    let mut pq = HashMapIPQ::<&str, i32>::default();
+    assert_eq!(pq.try_remove("a"), Err(Error::MissingPriority));
+    assert!(matches!(
+        pq.try_update_dyn("a"),
+        Err(Error::MissingPriority)
+    ));
+    assert_eq!(pq.try_restore_index("a"), Err(Error::MissingPriority));
+    assert_eq!(pq.try_push("a", 1), Ok(None));
+    assert_eq!(pq.try_push("a", 2), Ok(Some(1)));
+    *pq.try_update_up("a").unwrap() = 3;
+    assert_eq!(pq.try_remove("a"), Ok(3));
+
+    let mut pq = ArrayMapIPQ::<_, 1>::new(
+        vec![0; 2].into_boxed_slice(),
+        vec![usize::MAX; 2].into_boxed_slice(),
+    );
+    assert_eq!(pq.try_push(0, 1), Err(Error::OutOfRange));
+    assert_eq!(pq.try_push(3, 1), Err(Error::OutOfRange));
+    assert!(matches!(pq.try_update_down(0), Err(Error::OutOfRange)));
+    assert_eq!(pq.try_restore_index(3), Err(Error::OutOfRange));
+    assert!(!pq.contains(0));
+    assert_eq!(pq.get_priority(3), None);
+    assert_eq!(pq.try_push(2, 1), Ok(Some(0)));
+    assert_eq!(pq.pop(), Some(2));
+}
+
+#[test]
+#[cfg_attr(feature = "checked", should_panic)]
+fn validate() {
+    use indexed_priority_queue::InvariantViolation;
+
+    let mut pq = HashMapIPQ::<_, _>::from(vec![("a", 1), ("b", 2), ("c", 3)]);
+    assert_eq!(pq.validate(), Ok(()));
+
+    // Negating the priorities reverses their relative order.
+    pq.update_priorities_order_preserving(|p| *p = -*p);
+    assert_eq!(
+        pq.validate(),
+        Err(InvariantViolation::HeapOrder {
+            parent: 0,
+            child: 1
+        })
+    );
+}
+
+#[test]
+fn remove_index_moves_up() {
+    // Removing "e" moves the last index "g" into a subtree with larger priorities.
+    let mut pq = HashMapIPQ::<_, _>::from(vec![
+        ("a", 0),
+        ("b", 10),
+        ("c", 1),
+        ("d", 11),
+        ("e", 12),
+        ("f", 2),
+        ("g", 3),
+    ]);
+    pq.remove_index("e");
+    assert_eq!(pq.validate(), Ok(()));
+    assert_eq!(pq.into_sorted_vec(), ["a", "c", "f", "g", "b", "d"]);
+}
+
+#[test]
+fn max_order() {
+    use indexed_priority_queue::MaxHashMapIPQ;
+
+    let mut pq = MaxHashMapIPQ::<_, _>::default();
+    pq.push("a", 1);
+    pq.push("b", 2);
+    pq.push("f", 7);
+    assert_eq!(pq.pop(), Some("f"));
+    *pq.update_down("a") = 3;
+    assert_eq!(pq.into_sorted_vec(), ["a", "b"]);
+}
+
+#[test]
+fn closure_comparator() {
+    use indexed_priority_queue::hash_map::IndexedHashMap;
+    use indexed_priority_queue::IndexedPriorityQueue;
+
+    // Orders indices by a score stored outside of the queue, breaking ties by priority.
+    let scores = [3, 1, 2, 1];
+    let mut pq = IndexedPriorityQueue::<
+        usize,
+        IndexedHashMap<usize, usize>,
+        IndexedHashMap<usize, usize>,
+        2,
+        _,
+    >::with_comparator(
+        IndexedHashMap::default(),
+        IndexedHashMap::default(),
+        |a: &usize, b: &usize| scores[*a].cmp(&scores[*b]).then(b.cmp(a)),
+    );
+    for i in 0..4 {
+        pq.push(i, i);
+    }
+    assert_eq!(pq.into_sorted_vec(), [3, 1, 2, 0]);
+}
+
+#[test]
+fn non_clone_priorities() {
+    #[derive(Debug, Eq, PartialEq, Ord, PartialOrd)]
+    struct Cost(Vec<u32>);
+
+    let mut pq = HashMapIPQ::<_, _>::default();
+    pq.push("a", Cost(vec![1, 2]));
+    pq.push("b", Cost(vec![1, 3]));
+    pq.push("c", Cost(vec![0, 9]));
+    assert_eq!(pq.push("c", Cost(vec![2])), Some(Cost(vec![0, 9])));
+    pq.update_dyn("b").0[1] = 0;
+    assert_eq!(pq.remove("a"), Cost(vec![1, 2]));
+    assert_eq!(pq.into_sorted_vec(), ["b", "c"]);
+
+    #[derive(Debug, Default, Eq, PartialEq, Ord, PartialOrd)]
+    struct Steps(Vec<u32>);
+
+    let mut pq = DefaultMapIPQ::<_, _>::default();
+    pq.restore_index("a");
+    pq.push("b", Steps(vec![1]));
+    pq.update_dyn("a").0.push(2);
+    assert_eq!(pq.remove("b"), Steps(vec![1]));
+    assert_eq!(pq.remove("c"), Steps(vec![]));
+    assert_eq!(pq.into_sorted_vec(), ["a"]);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "`update_up` must not decrease the priority")]
+fn update_up_decrease() {
+    let mut pq = HashMapIPQ::<_, _>::from(vec![("a", 1), ("b", 2), ("c", 3)]);
+    *pq.update_up("c") = 0;
+}
+
+#[test]
+fn tie_break() {
+    use indexed_priority_queue::compare::MinOrder;
+    use indexed_priority_queue::hash_map::IndexedHashMap;
+    use indexed_priority_queue::tie_break::{Fifo, Lifo, RandomTieBreak, TieBreak};
+    use indexed_priority_queue::IndexedPriorityQueue;
+
+    type Pq<T> = IndexedPriorityQueue<
+        usize,
+        IndexedHashMap<usize, i32>,
+        IndexedHashMap<usize, usize>,
+        2,
+        MinOrder,
+        T,
+    >;
+
+    fn pop_order<T: TieBreak>(mut pq: Pq<T>) -> Vec<usize> {
+        for i in 0..8 {
+            pq.push(i, (i % 2) as i32);
+        }
+        // Restoring an index counts as entering the queue again.
+        pq.remove_index(0);
+        pq.restore_index(0);
+        std::iter::from_fn(|| pq.pop()).collect()
+    }
+
+    let fifo = Pq::<Fifo>::default();
+    assert_eq!(pop_order(fifo), [2, 4, 6, 0, 1, 3, 5, 7]);
+
+    let lifo = Pq::<Lifo>::default();
+    assert_eq!(pop_order(lifo), [0, 6, 4, 2, 7, 5, 3, 1]);
+
+    let random = |seed| {
+        Pq::with_tie_break(
+            IndexedHashMap::default(),
+            IndexedHashMap::default(),
+            RandomTieBreak::new(seed),
+        )
+    };
+    assert_eq!(pop_order(random(42)), pop_order(random(42)));
+    let mut order = pop_order(random(7));
+    order[..4].sort();
+    order[4..].sort();
+    assert_eq!(order, [0, 2, 4, 6, 1, 3, 5, 7]);
+}
+
+#[test]
+fn inline_layout() {
+    use indexed_priority_queue::InlineHashMapIPQ;
+
+    let mut pq = InlineHashMapIPQ::<&str, i32>::default();
+    for (index, priority) in [("a", 5), ("b", 3), ("c", 8), ("d", 1), ("e", 9)] {
+        pq.push(index, priority);
+    }
+
+    *pq.update_up("d") = 7;
+    *pq.update_down("e") = 2;
+    *pq.update_dyn("c") = 4;
+    pq.push("a", 0);
+    pq.remove_index("b");
+    *pq.update_down("b") = -1;
+    pq.restore_index("b");
+    pq.update_priorities_order_preserving(|priority| *priority *= 2);
+    assert_eq!(pq.validate(), Ok(()));
+
+    assert_eq!(pq.min_priority(), Some(&-2));
+    assert_eq!(pq.into_sorted_vec(), ["b", "a", "e", "c", "d"]);
+
+    // Repeated indices are rebuilt from their latest priority.
+    let mut pq = InlineHashMapIPQ::<_, _>::from_iter([("a", 1), ("b", 3), ("c", 4), ("a", 10)]);
+    assert_eq!(pq.validate(), Ok(()));
+    assert_eq!(pq.pop(), Some("b"));
+
+    // Priorities move between the heap and the map, so they need not be `Clone`.
+    #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+    struct Cost(i32);
+
+    let mut pq = InlineHashMapIPQ::<&str, Cost>::default();
+    for (index, priority) in [("a", 5), ("b", 3), ("c", 8), ("d", 1)] {
+        assert_eq!(pq.push(index, Cost(priority)), None);
+    }
+    assert_eq!(pq.push("c", Cost(2)), Some(Cost(8)));
+    assert_eq!(pq.pop(), Some("d"));
+    assert_eq!(pq.get_priority("d"), Some(&Cost(1)));
+    assert_eq!(pq.get_priority("c"), Some(&Cost(2)));
+    assert_eq!(pq.push("d", Cost(4)), Some(Cost(1)));
+    pq.update_down("a").0 = 0;
+    assert_eq!(pq.remove("b"), Cost(3));
+    assert_eq!(pq.iter().count(), 3);
+    assert_eq!(pq.validate(), Ok(()));
+    assert_eq!(
+        pq.drain_sorted().take(2).collect::<Vec<_>>(),
+        [("a", Cost(0)), ("c", Cost(2))]
+    );
+    assert!(pq.is_empty());
+    assert_eq!(pq.get_priority("d"), None);
+}
+
+#[test]
+fn randomized() {
+    use indexed_priority_queue::compare::MinOrder;
+    use indexed_priority_queue::hash_map::IndexedHashMap;
+    use indexed_priority_queue::layout::{Indirect, Inline, Layout};
+    use indexed_priority_queue::tie_break::Fifo;
+    use indexed_priority_queue::IndexedPriorityQueue;
+
+    fn run<const D: usize, L: Layout<u64>>() {
+        let n = 100;
+        let mut state = 5u64;
+        let mut next = || lcg(&mut state);
+        let mut pq = IndexedPriorityQueue::<
+            usize,
+            IndexedHashMap<usize, u64>,
+            IndexedHashMap<usize, usize>,
+            D,
+            MinOrder,
+            Fifo,
+            L,
+        >::default();
+        let mut reference = vec![None; n];
+
+        for _ in 0..5000 {
+            let index = next() as usize % n;
+            match next() % 6 {
+                0 | 1 => {
+                    let priority = next() % 100;
+                    pq.push(index, priority);
+                    reference[index] = Some(priority);
+                }
+                5 => {
+                    let pairs: Vec<_> = (0..next() % 64)
+                        .map(|_| (next() as usize % n, next() % 100))
+                        .collect();
+                    for &(index, priority) in &pairs {
+                        reference[index] = Some(priority);
+                    }
+                    pq.extend(pairs);
+                }
+                2 => {
+                    pq.remove_index(index);
+                    reference[index] = None;
+                }
+                3 => {
+                    if let Some(priority) = reference[index].as_mut() {
+                        *priority = next() % 100;
+                        *pq.update_dyn(index) = *priority;
+                    }
+                }
+                _ => {
+                    let min = reference.iter().flatten().min().copied();
+                    if let Some(index) = pq.pop() {
+                        assert_eq!(reference[index], min);
+                        reference[index] = None;
+                    }
+                }
+            }
+            assert_eq!(pq.validate(), Ok(()));
+            assert_eq!(pq.len(), reference.iter().flatten().count());
+        }
+    }
+
+    run::<2, Indirect>();
+    run::<4, Indirect>();
+    run::<2, Inline>();
+    run::<3, Inline>();
+}
+
+#[test]
+fn joint_hash_map() {
+    use indexed_priority_queue::JointHashMapIPQ;
+
+    let mut pq = JointHashMapIPQ::<&str, i32>::default();
+    for (index, priority) in [("a", 5), ("b", 3), ("c", 8), ("d", 1), ("e", 9)] {
+        assert_eq!(pq.push(index, priority), None);
+    }
+
+    assert_eq!(pq.push("a", 0), Some(5));
+    *pq.update_up("d") = 7;
+    *pq.update_down("e") = 2;
+    pq.remove_index("b");
+    assert!(!pq.contains("b"));
+    assert_eq!(pq.get_priority("b"), Some(&3));
+    assert_eq!(pq.push("b", -1), Some(3));
+    assert_eq!(pq.remove("c"), 8);
+    assert_eq!(pq.validate(), Ok(()));
+
+    assert_eq!(pq.pop(), Some("b"));
+    pq.restore_index("b");
+    assert_eq!(pq.len(), 4);
+    pq.clear_indices();
+    assert!(pq.is_empty());
+    pq.extend_indices(["a", "b", "d", "e"]);
+    assert_eq!(pq.validate(), Ok(()));
+    assert_eq!(pq.into_sorted_vec(), ["b", "a", "e", "d"]);
+
+    let mut pq = JointHashMapIPQ::<i32, i32>::default();
+    pq.extend([(1, 3), (2, 1), (3, 2)]);
+    assert_eq!(pq.drain_sorted().take(1).collect::<Vec<_>>(), [(2, 1)]);
+    assert!(pq.is_empty());
+    assert_eq!(pq.get_priority(1), None);
+    assert_eq!(pq.validate(), Ok(()));
+}
+
+#[test]
+fn hashers() {
+    use indexed_priority_queue::hash_map::IndexedHashMap;
+    use indexed_priority_queue::hasher::{BuildIntegerHasher, DeterministicState};
+    use indexed_priority_queue::indexed::Indexed;
+
+    let mut pq = HashMapIPQ::<u64, u64, BuildIntegerHasher>::default();
+    for index in 0..1000 {
+        pq.push(index * 1024, (index * 7919) % 1000);
+    }
+    *pq.update_down(0) = 0;
+    pq.remove_index(1024);
+    assert_eq!(pq.validate(), Ok(()));
+    let mut last = 0;
+    while let Some(index) = pq.pop() {
+        let priority = *pq.get_priority(index).unwrap();
+        assert!(priority >= last);
+        last = priority;
+    }
+
+    let mut pq = DefaultMapIPQ::<i32, u32, BuildIntegerHasher>::default();
+    *pq.update_down(-3) = 0;
+    pq.restore_index(-5);
+    assert_eq!(pq.into_sorted_vec(), [-5]);
+
+    let iteration_order = || {
+        let mut map = IndexedHashMap::<u32, u32, DeterministicState>::default();
+        for index in 0..100 {
+            map.insert(index, index);
+        }
+        map.iter().copied().collect::<Vec<_>>()
+    };
+    assert_eq!(iteration_order(), iteration_order());
+}
+
+#[test]
+fn growable_index_space() {
+    use indexed_priority_queue::{ArrayMapIPQ, Error, VecMapIPQ};
+
+    let mut pq = ArrayMapIPQ::<_, 1>::with_index_space(2, 0);
+    assert_eq!(pq.try_push(3, 1), Err(Error::OutOfRange));
+    pq.grow_index_space(3);
+    assert_eq!(pq.try_push(3, 1), Ok(None));
+    pq.restore_index(1);
+    *pq.update_down(2) = -1;
+    pq.restore_index(2);
+    pq.grow_index_space(1);
+    assert_eq!(pq.validate(), Ok(()));
+    assert_eq!(pq.into_sorted_vec(), [2, 1, 3]);
+
+    let mut pq = VecMapIPQ::<_, 1>::default();
+    assert_eq!(pq.try_push(0, 1), Err(Error::OutOfRange));
+    assert_eq!(pq.push(10, 5), None);
+    assert_eq!(pq.push(3, 7), None);
+    assert_eq!(pq.push(1000, 2), None);
+    assert_eq!(pq.get_priority(500), None);
+    assert!(!pq.contains(2000));
+    pq.remove_index(2000);
+    assert_eq!(pq.validate(), Ok(()));
+    assert_eq!(pq.pop(), Some(1000));
+    pq.clear();
+    assert_eq!(pq.get_priority(10), None);
+    assert!(!pq.contains(10));
+    pq.push(3, 4);
+    pq.clear_indices();
+    assert!(!pq.contains(3));
+    pq.restore_index(3);
+    assert_eq!(pq.validate(), Ok(()));
+
+    let mut pq = VecMapIPQ::<_>::with_index_space(3, 0);
+    pq.extend_indices(0..3);
+    pq.push(5, -1);
+    assert_eq!(pq.into_sorted_vec()[0], 5);
+}
+
+#[test]
+#[should_panic(expected = "index is out of range")]
+fn vec_map_below_offset() {
+    use indexed_priority_queue::VecMapIPQ;
+
+    let mut pq = VecMapIPQ::<_, 1>::default();
+    pq.push(0, 1);
+}
+
+#[test]
+fn array_map_clear() {
+    use indexed_priority_queue::array_map::{ArrayPositionMap, ArrayPriorityMap, Offset};
+    use indexed_priority_queue::indexed::Indexed;
+    use indexed_priority_queue::ArrayMapIPQ;
+
+    let mut pq = ArrayMapIPQ::<_, 1>::with_index_space(6, 0);
+    for index in 1..=6 {
+        pq.push(index, 10 - index as i32);
+    }
+    pq.clear_indices();
+    assert!(pq.is_empty());
+    assert!(!pq.contains(3));
+    assert_eq!(pq.get_priority(3), Some(&7));
+    pq.extend_indices([2, 4]);
+    assert_eq!(pq.validate(), Ok(()));
+
+    pq.clear();
+    assert_eq!(pq.get_priority(3), None);
+    assert_eq!(
+        pq.try_restore_index(3),
+        Err(indexed_priority_queue::Error::MissingPriority)
+    );
+    pq.push(3, 1);
+    pq.push(5, 0);
+    assert_eq!(pq.validate(), Ok(()));
+    assert_eq!(pq.into_sorted_vec(), [5, 3]);
+
+    let mut priorities = ArrayPriorityMap::<_>::from(vec![1, 2, 3].into_boxed_slice());
+    assert_eq!(priorities.remove(1), Some(2));
+    assert_eq!(priorities.get(1), None);
+    assert_eq!(priorities.iter().copied().collect::<Vec<_>>(), [1, 3]);
+    priorities.clear();
+    assert_eq!(priorities.iter().count(), 0);
+    assert_eq!(priorities.insert(2, 4), None);
+    assert_eq!(priorities.iter().copied().collect::<Vec<_>>(), [4]);
+
+    let mut positions =
+        ArrayPositionMap::<Offset>::from(vec![usize::MAX, 0, usize::MAX].into_boxed_slice());
+    assert_eq!(positions.get(0), None);
+    assert_eq!(positions.get(1), Some(&0));
+    positions.clear();
+    assert_eq!(positions.get(1), None);
+    assert_eq!(positions.insert(2, 5), None);
+    assert_eq!(positions.insert(2, 6), Some(5));
+    assert_eq!(positions.iter().copied().collect::<Vec<_>>(), [6]);
+}
+
+#[test]
+fn index_mappers() {
+    use indexed_priority_queue::array_map::{Dense, Grid, Literals};
+    use indexed_priority_queue::{Error, MappedArrayMapIPQ};
+
+    #[derive(Debug, Copy, Clone, PartialEq)]
+    struct NodeId(u32);
+
+    impl From<NodeId> for usize {
+        fn from(node: NodeId) -> Self {
+            node.0 as usize
+        }
+    }
+
+    let mut pq = MappedArrayMapIPQ::<u32, Dense<NodeId>>::with_index_space(4, 0);
+    pq.push(NodeId(3), 5);
+    pq.push(NodeId(0), 7);
+    pq.push(NodeId(2), 1);
+    assert_eq!(pq.try_push(NodeId(4), 0), Err(Error::OutOfRange));
+    assert_eq!(pq.into_sorted_vec(), [NodeId(2), NodeId(3), NodeId(0)]);
+
+    let mut pq = MappedArrayMapIPQ::<u32, Literals>::with_index_space(6, 0);
+    for (literal, activity) in [(1, 4), (-1, 2), (2, 6), (-2, 3), (3, 1), (-3, 5)] {
+        pq.push(literal, activity);
+    }
+    assert_eq!(pq.try_push(0, 0), Err(Error::OutOfRange));
+    assert_eq!(pq.try_push(4, 0), Err(Error::OutOfRange));
+    assert_eq!(pq.validate(), Ok(()));
+    assert_eq!(pq.into_sorted_vec(), [3, -1, -2, 1, -3, 2]);
+
+    let grid = Grid { width: 3 };
+    let mut pq = MappedArrayMapIPQ::<u32, Grid>::with_index_space_and_mapper(6, 0, grid);
+    pq.push((2, 1), 1);
+    pq.push((0, 1), 3);
+    pq.push((1, 0), 2);
+    assert_eq!(pq.try_push((3, 0), 0), Err(Error::OutOfRange));
+    assert_eq!(pq.try_push((0, 2), 0), Err(Error::OutOfRange));
+    pq.grow_index_space(9);
+    assert_eq!(pq.try_push((0, 2), 0), Ok(None));
+    assert_eq!(pq.into_sorted_vec(), [(0, 2), (2, 1), (1, 0), (0, 1)]);
+}
+
+#[test]
+fn compact_positions() {
+    use indexed_priority_queue::hash_map::IndexedHashMap;
+    use indexed_priority_queue::vec_map::{VecPositionMap, VecPriorityMap};
+    use indexed_priority_queue::{CompactArrayMapIPQ, Error, IndexedPriorityQueue};
+
+    let mut pq = CompactArrayMapIPQ::<_, 1>::with_index_space(8, 0);
+    for (index, priority) in (1..=8).zip([5, 3, 8, 1, 9, 2, 7, 4]) {
+        pq.push(index, priority);
+    }
+    *pq.update_down(3) = 0;
+    pq.remove_index(5);
+    assert_eq!(pq.validate(), Ok(()));
+    assert_eq!(pq.into_sorted_vec(), [3, 4, 6, 2, 8, 1, 7]);
+
+    let mut pq =
+        IndexedPriorityQueue::<u32, IndexedHashMap<u32, u32>, IndexedHashMap<u32, u16>>::default();
+    pq.extend((0..u16::MAX as u32).map(|index| (index, index % 100)));
+    assert_eq!(
+        pq.try_push(u16::MAX as u32, 0),
+        Err(Error::PositionOverflow)
+    );
+    assert_eq!(pq.try_push(7, 0), Ok(Some(7)));
+    pq.remove_index(7);
+    assert_eq!(pq.try_restore_index(7), Ok(()));
+    assert_eq!(pq.validate(), Ok(()));
+
+    let mut pq =
+        IndexedPriorityQueue::<usize, VecPriorityMap<u8>, VecPositionMap<0, u16>>::default();
+    pq.extend((0..u16::MAX as usize).map(|index| (index, 1)));
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        pq.push(u16::MAX as usize, 0);
+    }));
+    assert!(result.is_err());
+}
+
+#[test]
+fn default_priorities() {
+    use indexed_priority_queue::array_map::DefaultArrayMap;
+    use indexed_priority_queue::indexed::Indexed;
+    use indexed_priority_queue::DefaultArrayMapIPQ;
+
+    let mut pq = DefaultMapIPQ::<_, _>::with_default(u32::MAX);
+    assert_eq!(pq.get_priority("a"), Some(&u32::MAX));
+    *pq.update_down("a") = 3;
+    pq.restore_index("a");
+    pq.restore_index("b");
+    pq.push("c", 1);
+    assert_eq!(pq.into_sorted_vec(), ["c", "a", "b"]);
+
+    let mut pq = DefaultArrayMapIPQ::<_, 1>::with_index_space(4, usize::MAX);
+    assert_eq!(pq.get_priority(0), None);
+    assert_eq!(pq.get_priority(4), Some(&usize::MAX));
+    assert_eq!(pq.get_priority(5), None);
+    *pq.update_down(2) = 7;
+    pq.extend_indices([1, 2, 3]);
+    pq.push(4, 5);
+    assert_eq!(pq.validate(), Ok(()));
+    assert_eq!(pq.pop(), Some(4));
+    assert_eq!(pq.remove(2), 7);
+    assert_eq!(pq.get_priority(2), Some(&usize::MAX));
+    pq.clear();
+    assert_eq!(pq.get_priority(4), Some(&usize::MAX));
+    pq.grow_index_space(6);
+    pq.restore_index(6);
+    assert_eq!(pq.into_sorted_vec(), [6]);
+
+    // Indices queued with the default priority are removed with it.
+    let mut pq = DefaultArrayMapIPQ::<_>::with_index_space(4, 9);
+    pq.restore_index(1);
+    assert_eq!(pq.try_remove(1), Ok(9));
+    assert!(pq.is_empty());
+    let mut pq = DefaultMapIPQ::<_, _>::with_default(9);
+    pq.restore_index(1);
+    assert_eq!(pq.try_remove(1), Ok(9));
+    assert!(pq.is_empty());
+
+    let mut priorities = DefaultArrayMap::<_>::new(3, 0);
+    assert_eq!(priorities.insert(1, 4), None);
+    *priorities.index_mut(2) += 1;
+    assert_eq!(priorities.iter().copied().collect::<Vec<_>>(), [4, 1]);
+    assert_eq!(priorities.remove(0), Some(0));
+    priorities.clear();
+    assert_eq!(priorities.iter().count(), 0);
+    assert_eq!(priorities.get(1), Some(&0));
+}
+
+#[test]
+fn btree_map() {
+    use indexed_priority_queue::btree_map::IndexedBTreeMap;
+    use indexed_priority_queue::indexed::Indexed;
+    use indexed_priority_queue::BTreeMapIPQ;
+
+    let mut pq = BTreeMapIPQ::<_, _>::default();
+    for (index, priority) in [("d", 1), ("b", 5), ("e", 3), ("a", 4), ("c", 2)] {
+        pq.push(index, priority);
+    }
+    pq.remove_index("c");
+    *pq.update_up("d") = 6;
+    assert_eq!(pq.validate(), Ok(()));
+    assert_eq!(
+        pq.iter_ordered().collect::<Vec<_>>(),
+        [("a", &4), ("b", &5), ("d", &6), ("e", &3)]
+    );
+    assert_eq!(pq.into_sorted_vec(), ["e", "a", "b", "d"]);
+
+    let mut map = IndexedBTreeMap::default();
+    for index in [3, 1, 2] {
+        map.insert(index, index * 10);
+    }
+    assert_eq!(map.iter().copied().collect::<Vec<_>>(), [10, 20, 30]);
+    assert_eq!(map.pairs().next(), Some((&1, &10)));
+}
@@ -0,0 +1,127 @@
+mod common;
+
+use common::lcg;
+use indexed_priority_queue::{ArrayMapRadixIPQ, HashMapRadixIPQ};
+
+#[test]
+fn redistribution() {
+    let mut pq = HashMapRadixIPQ::<&str, u32>::default();
+    assert_eq!(pq.pop(), None);
+    // Relative to the initial last popped priority 0, 8 to 15 all share the bucket of bit 3.
+    for (index, priority) in [("a", 12), ("b", 9), ("c", 15), ("d", 8), ("e", 1 << 20)] {
+        pq.push(index, priority);
+    }
+    assert_eq!(pq.min_priority(), Some(&8));
+
+    // Popping redistributes that bucket relative to 8, spreading 9, 12 and 15 over lower buckets.
+    assert_eq!(pq.pop(), Some("d"));
+    assert_eq!(pq.last_popped(), 8);
+    *pq.update_down("c") = 10;
+
+    // Priorities equal to the last popped priority go to the first bucket and are popped directly.
+    assert_eq!(pq.push("f", 8), None);
+    assert_eq!(pq.pop(), Some("f"));
+    assert_eq!(pq.last_popped(), 8);
+
+    pq.remove_index("b");
+    assert!(!pq.contains("b"));
+    assert_eq!(pq.get_priority("b"), Some(&9));
+    assert_eq!(pq.pop(), Some("c"));
+    assert_eq!(pq.last_popped(), 10);
+
+    // The far away priority moves down to the bucket of bit 0 relative to 10.
+    *pq.update_dyn("e") = 11;
+    assert_eq!(pq.push("b", 10), Some(9));
+    assert_eq!(pq.remove("a"), 12);
+
+    let popped = std::iter::from_fn(|| pq.pop()).collect::<Vec<_>>();
+    assert_eq!(popped, ["b", "e"]);
+    assert_eq!(pq.last_popped(), 11);
+}
+
+#[test]
+fn extreme_keys() {
+    let mut pq = HashMapRadixIPQ::<&str, u8>::default();
+    pq.push("a", u8::MAX);
+    pq.push("b", 0);
+    pq.push("c", u8::MAX - 1);
+    assert_eq!(pq.pop(), Some("b"));
+    assert_eq!(pq.pop(), Some("c"));
+    assert_eq!(pq.last_popped(), u8::MAX - 1);
+
+    // The last bucket holds the keys whose highest bit differs from the last popped priority.
+    pq.push("b", u8::MAX);
+    assert_eq!(pq.len(), 2);
+    assert_eq!(
+        pq.pop().map(|index| *pq.get_priority(index).unwrap()),
+        Some(u8::MAX)
+    );
+    assert_eq!(
+        pq.pop().map(|index| *pq.get_priority(index).unwrap()),
+        Some(u8::MAX)
+    );
+    assert!(pq.is_empty());
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "last popped priority")]
+fn push_below_last_popped() {
+    let mut pq = HashMapRadixIPQ::<&str, u8>::default();
+    pq.push("a", 5);
+    pq.pop();
+    pq.push("b", 4);
+}
+
+#[test]
+fn randomized() {
+    let n = 200;
+    let mut state = 7;
+    let mut pq = ArrayMapRadixIPQ::<u64>::new(
+        vec![0; n].into_boxed_slice(),
+        vec![usize::MAX; n].into_boxed_slice(),
+    );
+    let mut reference = vec![None; n];
+
+    for step in 0..5000 {
+        let index = lcg(&mut state) as usize % n;
+        let last = pq.last_popped();
+        match lcg(&mut state) % 6 {
+            0 | 1 => {
+                // Priorities spread over many buckets, so that pops redistribute them repeatedly.
+                let priority = last + (lcg(&mut state) >> (lcg(&mut state) % 31));
+                pq.push(index, priority);
+                reference[index] = Some(priority);
+            }
+            2 => {
+                pq.remove_index(index);
+                reference[index] = None;
+            }
+            3 => {
+                if let Some(priority) = reference[index].as_mut() {
+                    *priority = last + (*priority - last) / 2;
+                    *pq.update_down(index) = *priority;
+                }
+            }
+            4 => {
+                if let Some(priority) = reference[index].as_mut() {
+                    *priority = last + lcg(&mut state) % 1000;
+                    *pq.update_dyn(index) = *priority;
+                }
+            }
+            _ => {
+                let min = reference.iter().flatten().min().copied();
+                assert_eq!(pq.min_priority().copied(), min);
+                if let Some(index) = pq.pop() {
+                    assert_eq!(reference[index], min);
+                    reference[index] = None;
+                }
+            }
+        }
+        if step % 1000 == 999 {
+            pq.clear_indices();
+            reference.fill(None);
+        }
+        assert_eq!(pq.len(), reference.iter().flatten().count());
+    }
+}